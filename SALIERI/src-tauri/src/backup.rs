@@ -0,0 +1,236 @@
+use std::{fs, path::PathBuf, time::Duration};
+use chrono::{Local, NaiveDateTime};
+use serde_json::json;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_store::StoreExt;
+
+use crate::config::load_config;
+use crate::fileaccess::write_atomic;
+use crate::journal::journal_files;
+use crate::storage::{self, data_dir};
+use crate::recurrence::{reload_rules, rules_path};
+use crate::tasks::reload_store;
+use crate::theme::SETTINGS_STORE_FILENAME;
+use crate::undo::clear_history;
+use crate::user::USER_STORE;
+
+pub const BACKUP_KEEP_KEY: &str = "backup_keep";
+pub const DEFAULT_BACKUP_KEEP: u64 = 10;
+
+const STAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+const DAY_SECS: i64 = 24 * 60 * 60;
+
+// ─── what gets backed up
+// task data lives under data_dir(); user.json and settings.json are owned by
+// tauri-plugin-store, which keeps them in the app data dir
+fn backup_sources(app: &AppHandle) -> Vec<PathBuf> {
+    let mut files = vec![storage::store_path(), storage::sqlite_path(), rules_path()];
+    // the snapshot alone is missing whatever hasn't been compacted yet
+    files.extend(journal_files());
+    if let Ok(app_dir) = app.path().app_data_dir() {
+        files.push(app_dir.join(USER_STORE));
+        files.push(app_dir.join(SETTINGS_STORE_FILENAME));
+    }
+    files
+}
+
+pub fn backups_dir() -> PathBuf { data_dir().join("backups") }
+
+fn backup_keep(app: &AppHandle) -> u64 {
+    app.store(SETTINGS_STORE_FILENAME)
+        .ok()
+        .and_then(|s| s.get(BACKUP_KEEP_KEY))
+        .and_then(|v| v.as_u64())
+        .unwrap_or(DEFAULT_BACKUP_KEEP)
+}
+
+// a backup's name is when it was taken, with "-2", "-3"... after it when
+// more than one was taken in the same second
+fn stamp_time(name: &str) -> Option<(NaiveDateTime, u32)> {
+    if let Ok(at) = NaiveDateTime::parse_from_str(name, STAMP_FORMAT) {
+        return Some((at, 1));
+    }
+    let (base, n) = name.rsplit_once('-')?;
+    let n: u32 = n.parse().ok().filter(|n| *n > 1)?;
+    NaiveDateTime::parse_from_str(base, STAMP_FORMAT).ok().map(|at| (at, n))
+}
+
+// newest first
+pub fn list_backups() -> Vec<String> {
+    let mut stamps: Vec<(NaiveDateTime, u32, String)> = fs::read_dir(backups_dir())
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter(|e| e.path().is_dir())
+                .map(|e| e.file_name().to_string_lossy().into_owned())
+                .filter_map(|name| stamp_time(&name).map(|(at, n)| (at, n, name)))
+                .collect()
+        })
+        .unwrap_or_default();
+    stamps.sort_unstable_by_key(|(at, n, _)| std::cmp::Reverse((*at, *n)));
+    stamps.into_iter().map(|(_, _, name)| name).collect()
+}
+
+// creates backups/<stamp>/, never reusing one that's already there
+fn new_backup_dir() -> Result<(String, PathBuf), String> {
+    let base = Local::now().format(STAMP_FORMAT).to_string();
+    fs::create_dir_all(backups_dir()).map_err(|e| format!("failed to create backup dir: {}", e))?;
+    for n in 1.. {
+        let stamp = if n == 1 { base.clone() } else { format!("{}-{}", base, n) };
+        let dir = backups_dir().join(&stamp);
+        match fs::create_dir(&dir) {
+            Ok(()) => return Ok((stamp, dir)),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(format!("failed to create backup dir: {}", e)),
+        }
+    }
+    unreachable!()
+}
+
+// copies every source that exists into a new backup, without touching older ones
+fn copy_sources(app: &AppHandle) -> Result<String, String> {
+    let (stamp, dir) = new_backup_dir()?;
+    for src in backup_sources(app).iter().filter(|p| p.exists()) {
+        let name = src.file_name().ok_or("backup source has no file name")?;
+        fs::copy(src, dir.join(name))
+            .map_err(|e| format!("failed to back up '{}': {}", src.display(), e))?;
+    }
+    Ok(stamp)
+}
+
+// takes a backup and trims old rotations
+pub fn create_backup(app: &AppHandle) -> Result<String, String> {
+    let stamp = copy_sources(app)?;
+    prune_backups(backup_keep(app))?;
+    Ok(stamp)
+}
+
+fn prune_backups(keep: u64) -> Result<(), String> {
+    for old in list_backups().iter().skip(keep.max(1) as usize) {
+        fs::remove_dir_all(backups_dir().join(old))
+            .map_err(|e| format!("failed to remove old backup {}: {}", old, e))?;
+    }
+    Ok(())
+}
+
+fn newest_backup_age_secs() -> Option<i64> {
+    let newest = list_backups().into_iter().next()?;
+    let (at, _) = stamp_time(&newest)?;
+    Some((Local::now().naive_local() - at).num_seconds())
+}
+
+// takes a backup at launch, then again whenever the newest one is a day old
+pub fn start_backup_loop(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = tokio::time::interval(Duration::from_secs(60 * 60));
+        loop {
+            ticker.tick().await;
+            if newest_backup_age_secs().is_some_and(|age| age < DAY_SECS) {
+                continue;
+            }
+            let h = app.clone();
+            match tauri::async_runtime::spawn_blocking(move || create_backup(&h)).await {
+                Ok(Ok(stamp)) => println!("daily backup taken: {}", stamp),
+                Ok(Err(e)) => eprintln!("daily backup failed: {}", e),
+                Err(e) => eprintln!("daily backup task failed: {}", e),
+            }
+        }
+    });
+}
+
+// ─── restore
+fn restore_files(app: &AppHandle, stamp: &str) -> Result<usize, String> {
+    let dir = backups_dir().join(stamp);
+    if !dir.is_dir() {
+        return Err(format!("no backup named '{}'. see /backup list", stamp));
+    }
+
+    let mut restored = 0;
+    for dest in backup_sources(app) {
+        let Some(name) = dest.file_name() else { continue };
+        let src = dir.join(name);
+        if !src.exists() {
+            // a journal left over from now would be replayed over the restored snapshot
+            if journal_files().contains(&dest) && dest.exists() {
+                fs::remove_file(&dest).map_err(|e| format!("failed to remove '{}': {}", dest.display(), e))?;
+            }
+            continue;
+        }
+        let bytes = fs::read(&src).map_err(|e| format!("failed to read '{}': {}", src.display(), e))?;
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        write_atomic(&dest, &bytes)?;
+        restored += 1;
+    }
+    Ok(restored)
+}
+
+pub async fn command_restore(parts: &[&str], app: AppHandle) -> Result<String, String> {
+    let Some(stamp) = parts.get(1).map(|s| s.to_string()) else {
+        return Err("usage: /restore <timestamp>. see /backup list".into());
+    };
+
+    // whatever we have now becomes its own backup, so a restore can be undone.
+    // nothing is pruned here: the backup being restored may be the oldest one
+    let h = app.clone();
+    let safety = tauri::async_runtime::spawn_blocking(move || {
+        let safety = copy_sources(&h)?;
+        restore_files(&h, &stamp).map(|n| (safety, n))
+    })
+    .await
+    .map_err(|e| format!("Failed to join restore task: {}", e))??;
+    let (safety, restored) = safety;
+
+    // reopen the task backend so a restored database isn't shadowed by the old connection
+    let kind = storage::StorageKind::parse(storage::backend().name()).unwrap_or(storage::StorageKind::Json);
+    storage::select_backend(kind)?;
+    reload_store().await?;
+    reload_rules();
+    clear_history();
+
+    for file in [USER_STORE, SETTINGS_STORE_FILENAME] {
+        if let Ok(s) = app.store(file) {
+            s.reload().map_err(|e| format!("failed to reload {}: {}", file, e))?;
+        }
+    }
+    // the daily limit and day start come from the restored settings straight away
+    load_config(&app)?;
+
+    Ok(format!("restored {} files from {}. previous state saved as {}", restored, parts[1], safety))
+}
+
+// ─── /backup
+pub async fn command_backup(parts: &[&str], app: AppHandle) -> Result<String, String> {
+    match parts.get(1) {
+        None => {
+            let stamp = tauri::async_runtime::spawn_blocking(move || create_backup(&app))
+                .await
+                .map_err(|e| format!("Failed to join backup task: {}", e))??;
+            Ok(format!("backup saved as {}", stamp))
+        }
+
+        Some(&"list") => {
+            let stamps = list_backups();
+            if stamps.is_empty() {
+                Ok("no backups yet".into())
+            } else {
+                Ok(stamps.join("\n"))
+            }
+        }
+
+        Some(&"keep") => {
+            let n: u64 = parts.get(2)
+                .and_then(|s| s.parse().ok())
+                .filter(|n| *n > 0)
+                .ok_or("usage: /backup keep <number of backups, at least 1>")?;
+            let settings = app.store(SETTINGS_STORE_FILENAME).map_err(|e| e.to_string())?;
+            settings.set(BACKUP_KEEP_KEY, json!(n));
+            settings.save().map_err(|e| e.to_string())?;
+            prune_backups(n)?;
+            Ok(format!("keeping the {} most recent backups", n))
+        }
+
+        Some(arg) => Err(format!("unknown /backup argument '{arg}'. use list or keep <n>.")),
+    }
+}
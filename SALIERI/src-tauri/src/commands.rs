@@ -2,9 +2,17 @@ use chrono::Local;
 use tauri::AppHandle;
 
 use crate::theme::{set_theme, get_current_theme};
-use crate::tasks::{command_todo, command_doing, command_done, command_break, command_completed, command_deleteT};
+use crate::tasks::{command_todo, command_doing, command_done, command_break, command_completed, command_deleteT, command_rollover, command_history, command_recover, command_rename, command_undone, command_move, command_find, command_tasks, command_sub, command_check, command_block, command_unblock, command_blocked};
 use crate::pomodoro::{command_start_pomodoro, command_pause_pomodoro, command_stop_pomodoro, command_resume_pomodoro};
 use crate::fileaccess::{command_code};
+use crate::storage::command_storage;
+use crate::backup::{command_backup, command_restore};
+use crate::config::command_config;
+use crate::recurrence::{self, command_every, command_recurring};
+use crate::undo::{self, command_undo, command_redo};
+use crate::reports::command_report;
+use crate::export::command_export;
+use crate::import::command_import;
 
 // file management
 
@@ -52,25 +60,62 @@ fn command_wq() -> Result<String, String>
 pub async fn handle_palette_command(command: String, app_handle: AppHandle, days_offset: Option<i64>) -> Result<String, String> {
     let trimmed_command = command.trim();
     let parts: Vec<&str> = trimmed_command.split_whitespace().collect();
+    if !undo::is_undoable(&parts) {
+        return dispatch(&parts, app_handle, days_offset).await;
+    }
+
+    // everything /undo should be able to take back: the tasks the command
+    // touches, plus the running task, theme and done count around it
+    let before = undo::checkpoint(&app_handle).await;
+    let (result, changes) = undo::track(dispatch(&parts, app_handle.clone(), days_offset)).await;
+    undo::record(trimmed_command, before, changes, result.is_ok(), &app_handle).await;
+    result
+}
 
+async fn dispatch(parts: &[&str], app_handle: AppHandle, days_offset: Option<i64>) -> Result<String, String> {
     match parts.get(0) {
         Some(&"ping") => command_ping(),
         Some(&"date") => command_date(),
-        Some(&"/theme") => command_theme(&parts, app_handle).await,
-        Some(&"/todo") => command_todo(&parts, app_handle).await,
-        Some(&"/doing") => command_doing(&parts, app_handle, days_offset).await,
-        Some(&"/done") => command_done(&parts, app_handle, days_offset).await,
-        Some(&"/break") => command_break(&parts, app_handle).await,
-        Some(&"/deleteT") => command_deleteT(&parts, app_handle).await,
-        Some(&"/completed") => command_completed(), 
+        Some(&"/theme") => command_theme(parts, app_handle).await,
+        Some(&"/todo") if recurrence::is_rule_command(parts) => command_every(parts, app_handle).await,
+        Some(&"/todo") => command_todo(parts, app_handle, days_offset).await,
+        Some(&"/doing") => command_doing(parts, app_handle, days_offset).await,
+        Some(&"/done") => command_done(parts, app_handle, days_offset).await,
+        Some(&"/break") => command_break(parts, app_handle, days_offset).await,
+        Some(&"/deleteT") => command_deleteT(parts, app_handle, days_offset).await,
+        Some(&"/rename") => command_rename(parts, app_handle, days_offset).await,
+        Some(&"/undone") => command_undone(parts, app_handle, days_offset).await,
+        Some(&"/move") => command_move(parts, app_handle, days_offset).await,
+        Some(&"/sub") => command_sub(parts, app_handle, days_offset).await,
+        Some(&"/check") => command_check(parts, app_handle, days_offset).await,
+        Some(&"/block") => command_block(parts, app_handle, days_offset).await,
+        Some(&"/unblock") => command_unblock(parts, app_handle, days_offset).await,
+        Some(&"/blocked") => command_blocked(parts, app_handle, days_offset).await,
+        Some(&"/completed") => command_completed(parts, app_handle).await,
+        Some(&"/history") => command_history(parts, app_handle, days_offset).await,
+        Some(&"/find") => command_find(parts, app_handle).await,
+        Some(&"/tasks") => command_tasks(parts, app_handle).await,
+        Some(&"/report") => command_report(parts, app_handle).await,
+        Some(&"/export") => command_export(parts, app_handle).await,
+        Some(&"/import") => command_import(parts, app_handle).await,
+        Some(&"/recover") => command_recover(parts, app_handle).await,
+        Some(&"/rollover") => command_rollover(parts, app_handle).await,
+        Some(&"/every") => command_every(parts, app_handle).await,
+        Some(&"/recurring") => command_recurring(parts, app_handle).await,
+        Some(&"/storage") => command_storage(parts, app_handle).await,
+        Some(&"/backup") => command_backup(parts, app_handle).await,
+        Some(&"/restore") => command_restore(parts, app_handle).await,
+        Some(&"/config") => command_config(parts, app_handle).await,
+        Some(&"/undo") => command_undo(parts, app_handle).await,
+        Some(&"/redo") => command_redo(parts, app_handle).await,
         Some(&"/start") => command_start_pomodoro().await,
         Some(&"/pause") => command_pause_pomodoro().await,
         Some(&"/resume") => command_resume_pomodoro().await,
         Some(&"/stop") => command_stop_pomodoro().await,
-        Some(&"/code") => command_code(&parts, app_handle).await,
-        Some(&"/write") => command_code(&parts, app_handle).await,
+        Some(&"/code") => command_code(parts, app_handle).await,
+        Some(&"/write") => command_code(parts, app_handle).await,
         Some(&"/wq") => command_wq(),
         Some(unknown_cmd) => Err(format!("unknown command: {}", unknown_cmd)),
         None => Err("empty command received".into()), 
     }
-}
\ No newline at end of file
+}
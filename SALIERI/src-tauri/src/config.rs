@@ -0,0 +1,148 @@
+use std::sync::RwLock;
+use lazy_static::lazy_static;
+use serde_json::json;
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+use crate::theme::SETTINGS_STORE_FILENAME;
+
+pub const DAILY_LIMIT_KEY: &str = "daily_task_limit";
+pub const DAY_START_HOUR_KEY: &str = "day_start_hour";
+pub const AUTO_ROLLOVER_KEY: &str = "auto_rollover";
+pub const RESUME_GAP_KEY: &str = "max_resume_gap_minutes";
+pub const AUTO_COMPLETE_KEY: &str = "auto_complete_checklists";
+
+pub const DEFAULT_DAILY_LIMIT: usize = 5;
+pub const DEFAULT_DAY_START_HOUR: u32 = 4;
+pub const DEFAULT_RESUME_GAP_MINUTES: u64 = 240;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Config {
+    // most tasks allowed in one day's todo list
+    pub daily_limit: usize,
+    // hour of the wall clock at which a new logical day begins
+    pub day_start_hour: u32,
+    // carry unfinished tasks into the new day when it starts
+    pub auto_rollover: bool,
+    // longest the app can be closed on a running task and still offer to
+    // pick it back up at launch. 0 turns the offer off
+    pub max_resume_gap_minutes: u64,
+    // finish a task once every item on its checklist is checked
+    pub auto_complete: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            daily_limit: DEFAULT_DAILY_LIMIT,
+            day_start_hour: DEFAULT_DAY_START_HOUR,
+            auto_rollover: true,
+            max_resume_gap_minutes: DEFAULT_RESUME_GAP_MINUTES,
+            auto_complete: false,
+        }
+    }
+}
+
+lazy_static! {
+    // cached copy of the settings store so non-async code like today_key can read it
+    static ref CONFIG: RwLock<Config> = RwLock::new(Config::default());
+}
+
+pub fn config() -> Config {
+    *CONFIG.read().unwrap()
+}
+
+pub fn load_config(app: &AppHandle) -> Result<(), String> {
+    let store = app.store(SETTINGS_STORE_FILENAME).map_err(|e| e.to_string())?;
+    let mut cfg = Config::default();
+    if let Some(n) = store.get(DAILY_LIMIT_KEY).and_then(|v| v.as_u64()).filter(|n| *n > 0) {
+        cfg.daily_limit = n as usize;
+    }
+    if let Some(h) = store.get(DAY_START_HOUR_KEY).and_then(|v| v.as_u64()).filter(|h| *h < 24) {
+        cfg.day_start_hour = h as u32;
+    }
+    if let Some(on) = store.get(AUTO_ROLLOVER_KEY).and_then(|v| v.as_bool()) {
+        cfg.auto_rollover = on;
+    }
+    if let Some(m) = store.get(RESUME_GAP_KEY).and_then(|v| v.as_u64()) {
+        cfg.max_resume_gap_minutes = m;
+    }
+    if let Some(on) = store.get(AUTO_COMPLETE_KEY).and_then(|v| v.as_bool()) {
+        cfg.auto_complete = on;
+    }
+    *CONFIG.write().unwrap() = cfg;
+    Ok(())
+}
+
+fn save_setting(app: &AppHandle, key: &str, value: serde_json::Value) -> Result<(), String> {
+    let store = app.store(SETTINGS_STORE_FILENAME).map_err(|e| e.to_string())?;
+    store.set(key, value);
+    store.save().map_err(|e| e.to_string())
+}
+
+fn describe(cfg: &Config) -> String {
+    format!(
+        "limit: {} tasks per day\ndaystart: {}:00\nautorollover: {}\nresumegap: {} minutes\nautocomplete: {}",
+        cfg.daily_limit, cfg.day_start_hour, if cfg.auto_rollover { "on" } else { "off" }, cfg.max_resume_gap_minutes,
+        if cfg.auto_complete { "on" } else { "off" }
+    )
+}
+
+// ─── /config
+pub async fn command_config(parts: &[&str], app: AppHandle) -> Result<String, String> {
+    match (parts.get(1), parts.get(2)) {
+        (None, _) => Ok(describe(&config())),
+
+        (Some(&"limit"), Some(n)) => {
+            let n: usize = n.parse().ok().filter(|n| *n > 0)
+                .ok_or("the daily limit must be a whole number above 0")?;
+            save_setting(&app, DAILY_LIMIT_KEY, json!(n))?;
+            CONFIG.write().unwrap().daily_limit = n;
+            Ok(format!("daily task limit set to {}", n))
+        }
+
+        (Some(&"daystart"), Some(h)) => {
+            let h: u32 = h.parse().ok().filter(|h| *h < 24)
+                .ok_or("the day start must be an hour from 0 to 23")?;
+            save_setting(&app, DAY_START_HOUR_KEY, json!(h))?;
+            CONFIG.write().unwrap().day_start_hour = h;
+            Ok(format!("new days now start at {}:00", h))
+        }
+
+        (Some(&"autorollover"), Some(v)) => {
+            let on = match *v {
+                "on" => true,
+                "off" => false,
+                _ => return Err("usage: /config autorollover [on|off]".into()),
+            };
+            save_setting(&app, AUTO_ROLLOVER_KEY, json!(on))?;
+            CONFIG.write().unwrap().auto_rollover = on;
+            Ok(format!("automatic rollover {}", v))
+        }
+
+        (Some(&"resumegap"), Some(m)) => {
+            let m: u64 = m.parse().map_err(|_| "the resume gap must be a whole number of minutes (0 turns it off)")?;
+            save_setting(&app, RESUME_GAP_KEY, json!(m))?;
+            CONFIG.write().unwrap().max_resume_gap_minutes = m;
+            if m == 0 {
+                Ok("won't offer to resume a task left running".into())
+            } else {
+                Ok(format!("tasks left running up to {} minutes can be resumed at launch", m))
+            }
+        }
+
+        (Some(&"autocomplete"), Some(v)) => {
+            let on = match *v {
+                "on" => true,
+                "off" => false,
+                _ => return Err("usage: /config autocomplete [on|off]".into()),
+            };
+            save_setting(&app, AUTO_COMPLETE_KEY, json!(on))?;
+            CONFIG.write().unwrap().auto_complete = on;
+            Ok(format!("checklist auto-complete {}", v))
+        }
+
+        (Some(key @ (&"limit" | &"daystart" | &"autorollover" | &"resumegap" | &"autocomplete")), None) => Err(format!("usage: /config {} <value>", key)),
+        (Some(arg), _) => Err(format!("unknown /config setting '{arg}'. use limit, daystart, autorollover, resumegap, or autocomplete.")),
+    }
+}
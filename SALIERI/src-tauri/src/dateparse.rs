@@ -0,0 +1,314 @@
+// date words for /todo, resolved against the current logical day.
+//
+// leading:  /todo 06/01/2025 write report, /todo fri write report,
+//           /todo next monday write report, /todo in 2 weeks write report
+// trailing: /todo write report by friday, /todo write report on 2025-06-01,
+//           /todo write report tomorrow, /todo write report +3d
+use chrono::{Datelike, Duration as ChronoDuration, NaiveDate};
+
+use crate::recurrence::parse_weekday;
+
+// two-digit years go first, and the year-first form last: %Y would happily
+// read "25" in 06-01-25 as the year 25
+const NUMERIC_FORMATS: [&str; 5] = [
+    "%m/%d/%y",    // 06/01/25
+    "%m/%d/%Y",    // 06/01/2025
+    "%m-%d-%y",    // 06-01-25
+    "%m-%d-%Y",    // 06-01-2025
+    "%Y-%m-%d",    // 2025-06-01
+];
+
+// words that introduce a trailing date
+const CONNECTORS: [&str; 3] = ["by", "on", "due"];
+
+fn parse_count(s: &str) -> Option<i64> {
+    match s {
+        "a" | "an" | "one" => Some(1),
+        "two" => Some(2),
+        "three" => Some(3),
+        _ => s.parse().ok().filter(|n| *n >= 0),
+    }
+}
+
+// "3d", "2w" (after the +)
+fn parse_offset(s: &str) -> Option<ChronoDuration> {
+    // split on the last char, not byte, so "3é" is just not an offset
+    let (at, unit) = s.char_indices().next_back()?;
+    let n = parse_count(&s[..at])?;
+    match unit {
+        'd' => Some(ChronoDuration::days(n)),
+        'w' => Some(ChronoDuration::weeks(n)),
+        _ => None,
+    }
+}
+
+fn unit_duration(unit: &str, n: i64) -> Option<ChronoDuration> {
+    match unit {
+        "d" | "day" | "days" => Some(ChronoDuration::days(n)),
+        "w" | "wk" | "wks" | "week" | "weeks" => Some(ChronoDuration::weeks(n)),
+        _ => None,
+    }
+}
+
+// soonest `wd` strictly after today
+fn upcoming(today: NaiveDate, wd: chrono::Weekday) -> NaiveDate {
+    let ahead = (wd.num_days_from_monday() as i64 - today.weekday().num_days_from_monday() as i64 + 7) % 7;
+    today + ChronoDuration::days(if ahead == 0 { 7 } else { ahead })
+}
+
+// `wd` in the calendar week (monday to sunday) after this one
+fn next_week(today: NaiveDate, wd: chrono::Weekday) -> NaiveDate {
+    let this_monday = today - ChronoDuration::days(today.weekday().num_days_from_monday() as i64);
+    this_monday + ChronoDuration::weeks(1) + ChronoDuration::days(wd.num_days_from_monday() as i64)
+}
+
+// the whole of `words` has to be a date for this to succeed
+pub fn parse_phrase(words: &[&str], today: NaiveDate) -> Option<NaiveDate> {
+    let lower: Vec<String> = words.iter().map(|w| w.to_lowercase()).collect();
+    let lower: Vec<&str> = lower.iter().map(|s| s.as_str()).collect();
+
+    match lower.as_slice() {
+        [w] => {
+            if let Some(d) = NUMERIC_FORMATS.iter().find_map(|f| NaiveDate::parse_from_str(w, f).ok()) {
+                return Some(d);
+            }
+            match *w {
+                "today" | "tod" => Some(today),
+                "tomorrow" | "tmr" | "tmrw" => Some(today + ChronoDuration::days(1)),
+                _ => {
+                    if let Some(rest) = w.strip_prefix('+') {
+                        return parse_offset(rest).map(|d| today + d);
+                    }
+                    parse_weekday(w).map(|wd| upcoming(today, wd))
+                }
+            }
+        }
+        ["next", "week"] => Some(next_week(today, chrono::Weekday::Mon)),
+        ["next", w] => parse_weekday(w).map(|wd| next_week(today, wd)),
+        ["in", n, unit] => parse_count(n).and_then(|n| unit_duration(unit, n)).map(|d| today + d),
+        _ => None,
+    }
+}
+
+// trailing phrases that read as a date even without "by"/"on"
+fn is_bare_trailing(words: &[&str]) -> bool {
+    match words {
+        [w] => {
+            let w = w.to_lowercase();
+            w.starts_with('+') || matches!(w.as_str(), "today" | "tomorrow" | "tmr" | "tmrw")
+        }
+        [first, ..] => matches!(first.to_lowercase().as_str(), "in" | "next"),
+        [] => false,
+    }
+}
+
+// pulls a date off the front or back of a /todo title. returns the date (if
+// any) and the words left over for the title, which is never left empty.
+pub fn extract_date<'a>(words: &[&'a str], today: NaiveDate) -> (Option<NaiveDate>, Vec<&'a str>) {
+    for n in (1..=3).rev() {
+        if words.len() > n {
+            if let Some(d) = parse_phrase(&words[..n], today) {
+                return (Some(d), words[n..].to_vec());
+            }
+        }
+    }
+
+    for n in (1..=3).rev() {
+        if words.len() > n + 1 {
+            let connector = words[words.len() - n - 1].to_lowercase();
+            if CONNECTORS.contains(&connector.as_str()) {
+                if let Some(d) = parse_phrase(&words[words.len() - n..], today) {
+                    return (Some(d), words[..words.len() - n - 1].to_vec());
+                }
+            }
+        }
+        if words.len() > n {
+            let tail = &words[words.len() - n..];
+            if is_bare_trailing(tail) {
+                if let Some(d) = parse_phrase(tail, today) {
+                    return (Some(d), words[..words.len() - n].to_vec());
+                }
+            }
+        }
+    }
+
+    (None, words.to_vec())
+}
+
+// for commands that end in a date, like /move <task> [to] <date>. the date
+// has to be at the end and something has to be left in front of it.
+pub fn split_trailing_date<'a>(words: &[&'a str], today: NaiveDate) -> Option<(NaiveDate, Vec<&'a str>)> {
+    for n in (1..=3).rev() {
+        if words.len() <= n {
+            continue;
+        }
+        let Some(d) = parse_phrase(&words[words.len() - n..], today) else { continue };
+        let mut rest = words[..words.len() - n].to_vec();
+        if rest.len() > 1 && rest.last().is_some_and(|w| w.eq_ignore_ascii_case("to")) {
+            rest.pop();
+        }
+        return Some((d, rest));
+    }
+    None
+}
+
+// ─── ranges
+// inclusive (first, last) for history queries: today, yesterday, this/last
+// week or month, last 7 days, 2025-06-01..2025-06-30, or any single date
+pub fn parse_range(words: &[&str], today: NaiveDate) -> Option<(NaiveDate, NaiveDate)> {
+    let lower: Vec<String> = words.iter().map(|w| w.to_lowercase()).collect();
+    let lower: Vec<&str> = lower.iter().map(|s| s.as_str()).collect();
+    let monday = today - ChronoDuration::days(today.weekday().num_days_from_monday() as i64);
+    let first_of_month = today.with_day(1)?;
+
+    match lower.as_slice() {
+        ["yesterday"] => {
+            let d = today - ChronoDuration::days(1);
+            return Some((d, d));
+        }
+        ["this", "week"] => return Some((monday, monday + ChronoDuration::days(6))),
+        ["last", "week"] => return Some((monday - ChronoDuration::weeks(1), monday - ChronoDuration::days(1))),
+        ["this", "month"] => {
+            let next = (first_of_month + ChronoDuration::days(31)).with_day(1)?;
+            return Some((first_of_month, next - ChronoDuration::days(1)));
+        }
+        ["last", "month"] => {
+            let last = first_of_month - ChronoDuration::days(1);
+            return Some((last.with_day(1)?, last));
+        }
+        ["last", n, unit] => {
+            let n = parse_count(n)?;
+            let span = unit_duration(unit, n)?;
+            return Some((today - span + ChronoDuration::days(1), today));
+        }
+        [w] if w.contains("..") => {
+            let (a, b) = w.split_once("..")?;
+            let a = parse_phrase(&[a], today)?;
+            let b = parse_phrase(&[b], today)?;
+            return Some((a.min(b), a.max(b)));
+        }
+        _ => {}
+    }
+    parse_phrase(words, today).map(|d| (d, d))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a wednesday
+    fn today() -> NaiveDate { NaiveDate::from_ymd_opt(2025, 6, 4).unwrap() }
+
+    fn ymd(y: i32, m: u32, d: u32) -> NaiveDate { NaiveDate::from_ymd_opt(y, m, d).unwrap() }
+
+    fn extract(s: &str) -> (Option<NaiveDate>, String) {
+        let words: Vec<&str> = s.split_whitespace().collect();
+        let (d, rest) = extract_date(&words, today());
+        (d, rest.join(" "))
+    }
+
+    #[test]
+    fn numeric_formats() {
+        for s in ["06/01/2025", "06/01/25", "2025-06-01", "06-01-2025", "06-01-25"] {
+            assert_eq!(parse_phrase(&[s], today()), Some(ymd(2025, 6, 1)), "{}", s);
+        }
+    }
+
+    #[test]
+    fn today_and_tomorrow() {
+        assert_eq!(parse_phrase(&["today"], today()), Some(today()));
+        assert_eq!(parse_phrase(&["Tomorrow"], today()), Some(ymd(2025, 6, 5)));
+        assert_eq!(parse_phrase(&["tmrw"], today()), Some(ymd(2025, 6, 5)));
+    }
+
+    #[test]
+    fn weekday_names_are_the_next_one_after_today() {
+        assert_eq!(parse_phrase(&["fri"], today()), Some(ymd(2025, 6, 6)));
+        assert_eq!(parse_phrase(&["monday"], today()), Some(ymd(2025, 6, 9)));
+        // today is wednesday, so "wed" is a week out
+        assert_eq!(parse_phrase(&["wed"], today()), Some(ymd(2025, 6, 11)));
+    }
+
+    #[test]
+    fn next_weekday_is_in_the_following_week() {
+        assert_eq!(parse_phrase(&["next", "monday"], today()), Some(ymd(2025, 6, 9)));
+        assert_eq!(parse_phrase(&["next", "fri"], today()), Some(ymd(2025, 6, 13)));
+        assert_eq!(parse_phrase(&["next", "week"], today()), Some(ymd(2025, 6, 9)));
+    }
+
+    #[test]
+    fn relative_offsets() {
+        assert_eq!(parse_phrase(&["+3d"], today()), Some(ymd(2025, 6, 7)));
+        assert_eq!(parse_phrase(&["+2w"], today()), Some(ymd(2025, 6, 18)));
+        assert_eq!(parse_phrase(&["in", "2", "weeks"], today()), Some(ymd(2025, 6, 18)));
+        assert_eq!(parse_phrase(&["in", "a", "week"], today()), Some(ymd(2025, 6, 11)));
+        assert_eq!(parse_phrase(&["in", "10", "days"], today()), Some(ymd(2025, 6, 14)));
+        assert_eq!(parse_phrase(&["+3x"], today()), None);
+    }
+
+    #[test]
+    fn non_ascii_offsets_are_not_dates() {
+        assert_eq!(parse_phrase(&["+3é"], today()), None);
+        assert_eq!(parse_phrase(&["+é"], today()), None);
+        assert_eq!(parse_phrase(&["+"], today()), None);
+        assert_eq!(extract("buy milk +3é"), (None, "buy milk +3é".into()));
+    }
+
+    #[test]
+    fn leading_forms() {
+        assert_eq!(extract("fri write report"), (Some(ymd(2025, 6, 6)), "write report".into()));
+        assert_eq!(extract("next monday plan sprint"), (Some(ymd(2025, 6, 9)), "plan sprint".into()));
+        assert_eq!(extract("in 2 weeks renew passport"), (Some(ymd(2025, 6, 18)), "renew passport".into()));
+        assert_eq!(extract("2025-06-01 old format"), (Some(ymd(2025, 6, 1)), "old format".into()));
+    }
+
+    #[test]
+    fn trailing_forms() {
+        assert_eq!(extract("write report by friday"), (Some(ymd(2025, 6, 6)), "write report".into()));
+        assert_eq!(extract("dentist on 06/10/2025"), (Some(ymd(2025, 6, 10)), "dentist".into()));
+        assert_eq!(extract("send invoice due next mon"), (Some(ymd(2025, 6, 9)), "send invoice".into()));
+        assert_eq!(extract("call mom tomorrow"), (Some(ymd(2025, 6, 5)), "call mom".into()));
+        assert_eq!(extract("water plants +3d"), (Some(ymd(2025, 6, 7)), "water plants".into()));
+        assert_eq!(extract("review draft in 3 days"), (Some(ymd(2025, 6, 7)), "review draft".into()));
+    }
+
+    #[test]
+    fn trailing_date_for_move() {
+        let split = |s: &str| {
+            let words: Vec<&str> = s.split_whitespace().collect();
+            split_trailing_date(&words, today()).map(|(d, rest)| (d, rest.join(" ")))
+        };
+        assert_eq!(split("write report to friday"), Some((ymd(2025, 6, 6), "write report".into())));
+        assert_eq!(split("2 next mon"), Some((ymd(2025, 6, 9), "2".into())));
+        assert_eq!(split("write report in 2 weeks"), Some((ymd(2025, 6, 18), "write report".into())));
+        assert_eq!(split("write report"), None);
+        assert_eq!(split("tomorrow"), None);
+    }
+
+    #[test]
+    fn ranges() {
+        let range = |s: &str| {
+            let words: Vec<&str> = s.split_whitespace().collect();
+            parse_range(&words, today())
+        };
+        assert_eq!(range("today"), Some((today(), today())));
+        assert_eq!(range("yesterday"), Some((ymd(2025, 6, 3), ymd(2025, 6, 3))));
+        assert_eq!(range("this week"), Some((ymd(2025, 6, 2), ymd(2025, 6, 8))));
+        assert_eq!(range("last week"), Some((ymd(2025, 5, 26), ymd(2025, 6, 1))));
+        assert_eq!(range("this month"), Some((ymd(2025, 6, 1), ymd(2025, 6, 30))));
+        assert_eq!(range("last month"), Some((ymd(2025, 5, 1), ymd(2025, 5, 31))));
+        assert_eq!(range("last 7 days"), Some((ymd(2025, 5, 29), today())));
+        assert_eq!(range("2025-06-01..2025-06-30"), Some((ymd(2025, 6, 1), ymd(2025, 6, 30))));
+        assert_eq!(range("06/30/2025..06/01/2025"), Some((ymd(2025, 6, 1), ymd(2025, 6, 30))));
+        assert_eq!(range("2025-05-20"), Some((ymd(2025, 5, 20), ymd(2025, 5, 20))));
+        assert_eq!(range("whenever"), None);
+    }
+
+    #[test]
+    fn titles_without_dates_are_left_alone() {
+        assert_eq!(extract("read 1984"), (None, "read 1984".into()));
+        assert_eq!(extract("prepare friday slides"), (None, "prepare friday slides".into()));
+        // a date on its own isn't a title
+        assert_eq!(extract("tomorrow"), (None, "tomorrow".into()));
+    }
+}
@@ -0,0 +1,219 @@
+// /export: task history as csv for spreadsheets, markdown for notes, or
+// icalendar todos for calendar apps
+use chrono::{Local, NaiveDate, Utc};
+use tauri::AppHandle;
+
+use crate::dateparse;
+use crate::fileaccess::{resolve_path, write_atomic};
+use crate::tasks::{format_duration, get_current_logical_day_key, snapshot_store, LogicalDay, Task, TaskStatus};
+
+// relative export paths land in ~/salieri_files/exports/
+const EXPORT_DIR: &str = "exports";
+
+// same names as the task fields, so a sheet can be read back by column
+// tags are space separated; estimate is in seconds like time_spent
+const CSV_HEADER: &str = "id,day,title,status,time_spent,created_at,tags,project,priority,estimate";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Csv,
+    Markdown,
+    Ical,
+}
+
+impl Format {
+    fn parse(s: &str) -> Option<Format> {
+        match s {
+            "csv" => Some(Format::Csv),
+            "md" | "markdown" => Some(Format::Markdown),
+            "ics" | "ical" => Some(Format::Ical),
+            _ => None,
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Format::Csv => "csv",
+            Format::Markdown => "md",
+            Format::Ical => "ics",
+        }
+    }
+}
+
+// ─── csv
+// quoted only when it has to be, doubling any quotes inside
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+fn to_csv(tasks: &[(&LogicalDay, &Task)]) -> String {
+    let mut out = String::from(CSV_HEADER);
+    out.push('\n');
+    for (day, t) in tasks {
+        let tags = t.tags.join(" ");
+        let priority = t.priority.map(|p| p.to_string()).unwrap_or_default();
+        let estimate = t.estimate.map(|e| e.to_string()).unwrap_or_default();
+        let row = [t.id.as_str(), day.as_str(), t.title.as_str(), t.status.as_str(), &t.time_spent.to_string(), t.created_at.as_str(), &tags, t.project.as_deref().unwrap_or(""), &priority, &estimate];
+        out.push_str(&row.iter().map(|f| csv_field(f)).collect::<Vec<_>>().join(","));
+        out.push('\n');
+    }
+    out
+}
+
+// ─── markdown
+fn to_markdown(tasks: &[(&LogicalDay, &Task)], from: &str, to: &str) -> String {
+    let mut out = if from == to { format!("# Tasks for {}\n", from) } else { format!("# Tasks {} to {}\n", from, to) };
+    let mut current: Option<&LogicalDay> = None;
+    for (day, t) in tasks {
+        if current != Some(*day) {
+            out.push_str(&format!("\n## {}\n\n", day));
+            current = Some(*day);
+        }
+        let mark = if t.status == TaskStatus::Done { "x" } else { " " };
+        // keep titles from turning into markup
+        let title = t.title.replace('\\', "\\\\").replace('*', "\\*").replace('_', "\\_").replace('`', "\\`");
+        out.push_str(&format!("- [{}] {}{}", mark, title, t.labels()));
+        if t.time_spent > 0 {
+            out.push_str(&format!(" ({})", format_duration(t.time_spent)));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+// ─── icalendar
+fn ics_text(s: &str) -> String {
+    s.replace('\\', "\\\\").replace(';', "\\;").replace(',', "\\,").replace('\n', "\\n")
+}
+
+// content lines are folded at 75 octets, continuing with a space
+fn ics_line(out: &mut String, line: &str) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+fn ics_date(day: &str) -> String {
+    day.replace('-', "")
+}
+
+// DUE has to come after DTSTART, so a task due on a day is due as it ends
+fn ics_due(day: &str) -> String {
+    NaiveDate::parse_from_str(day, "%Y-%m-%d").ok()
+        .and_then(|d| d.succ_opt())
+        .map(|d| d.format("%Y%m%d").to_string())
+        .unwrap_or_else(|| ics_date(day))
+}
+
+fn to_ical(tasks: &[(&LogicalDay, &Task)]) -> String {
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let mut out = String::new();
+    ics_line(&mut out, "BEGIN:VCALENDAR");
+    ics_line(&mut out, "VERSION:2.0");
+    ics_line(&mut out, "PRODID:-//salieri//tasks//EN");
+    for (day, t) in tasks {
+        ics_line(&mut out, "BEGIN:VTODO");
+        ics_line(&mut out, &format!("UID:{}@salieri", t.id));
+        ics_line(&mut out, &format!("DTSTAMP:{}", stamp));
+        ics_line(&mut out, &format!("DTSTART;VALUE=DATE:{}", ics_date(day)));
+        ics_line(&mut out, &format!("DUE;VALUE=DATE:{}", ics_due(day)));
+        ics_line(&mut out, &format!("SUMMARY:{}", ics_text(&t.title)));
+        let status = match t.status {
+            TaskStatus::Todo => "NEEDS-ACTION",
+            TaskStatus::Doing => "IN-PROCESS",
+            TaskStatus::Done => "COMPLETED",
+        };
+        ics_line(&mut out, &format!("STATUS:{}", status));
+        if let Some(p) = t.priority {
+            // icalendar's 1..9 scale, 1 being highest
+            ics_line(&mut out, &format!("PRIORITY:{}", [1, 5, 9][p.clamp(1, 3) as usize - 1]));
+        }
+        if !t.tags.is_empty() {
+            ics_line(&mut out, &format!("CATEGORIES:{}", t.tags.iter().map(|c| ics_text(c)).collect::<Vec<_>>().join(",")));
+        }
+        if t.time_spent > 0 {
+            ics_line(&mut out, &format!("DESCRIPTION:{}", ics_text(&format!("time spent: {}", format_duration(t.time_spent)))));
+        }
+        ics_line(&mut out, "END:VTODO");
+    }
+    ics_line(&mut out, "END:VCALENDAR");
+    out
+}
+
+// ─── /export csv|md|ics [range] [path]
+// a last word starting with ~ or ending in the format's extension is the
+// path; everything between is the range, which defaults to every day
+pub async fn command_export(parts: &[&str], _app: AppHandle) -> Result<String, String> {
+    let usage = "usage: /export csv|md|ics [range] [path]";
+    let format = Format::parse(parts.get(1).ok_or(usage)?)
+        .ok_or_else(|| format!("unknown export format '{}'. use csv, md or ics", parts[1]))?;
+    let mut words = &parts[2..];
+
+    let ext = format!(".{}", format.extension());
+    let path_arg = match words.last() {
+        Some(w) if w.starts_with('~') || w.ends_with(&ext) => {
+            words = &words[..words.len() - 1];
+            Some(*w)
+        }
+        _ => None,
+    };
+
+    let st = snapshot_store().await;
+    let mut days: Vec<&LogicalDay> = st.keys().collect();
+    days.sort();
+
+    let (from, to) = if words.is_empty() {
+        match (days.first(), days.last()) {
+            (Some(a), Some(b)) => (a.to_string(), b.to_string()),
+            _ => return Err("no tasks to export".into()),
+        }
+    } else {
+        let today = NaiveDate::parse_from_str(&get_current_logical_day_key(None), "%Y-%m-%d").map_err(|e| e.to_string())?;
+        let (a, b) = dateparse::parse_range(words, today)
+            .ok_or_else(|| format!("couldn't understand '{}'. try last week, this month or 2025-06-01..2025-06-30", words.join(" ")))?;
+        (a.format("%Y-%m-%d").to_string(), b.format("%Y-%m-%d").to_string())
+    };
+
+    let tasks: Vec<(&LogicalDay, &Task)> = days.into_iter()
+        .filter(|d| d.as_str() >= from.as_str() && d.as_str() <= to.as_str())
+        .flat_map(|d| {
+            let b = &st[d];
+            b.todo.values().chain(b.done.values()).map(move |t| (d, t))
+        })
+        .collect();
+    if tasks.is_empty() {
+        return Err(format!("no tasks between {} and {}", from, to));
+    }
+
+    let contents = match format {
+        Format::Csv => to_csv(&tasks),
+        Format::Markdown => to_markdown(&tasks, &from, &to),
+        Format::Ical => to_ical(&tasks),
+    };
+
+    let path = match path_arg {
+        Some(p) if p.starts_with('~') => resolve_path(p)?,
+        Some(p) => resolve_path(&format!("{}/{}", EXPORT_DIR, p))?,
+        None => {
+            let stamp = Local::now().format("%Y%m%d-%H%M%S");
+            resolve_path(&format!("{}/salieri-{}.{}", EXPORT_DIR, stamp, format.extension()))?
+        }
+    };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("failed to create directories: {}", e))?;
+    }
+    write_atomic(&path, contents.as_bytes())?;
+
+    Ok(format!("exported {} task(s) to {}", tasks.len(), path.display()))
+}
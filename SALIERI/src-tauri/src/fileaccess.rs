@@ -1,8 +1,44 @@
 use dirs_next::home_dir;
 use std::io::prelude::*;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tauri::AppHandle;
 use std::fs::File;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+// numbers each write's temp file, so two saves of one file never share it
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+// write to a sibling temp file, fsync it, then rename over the target so a
+// crash leaves either the old contents or the new ones, never half of each
+pub(crate) fn write_atomic(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    let dir = match path.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    let name = path.file_name()
+        .ok_or_else(|| format!("'{}' has no file name", path.display()))?
+        .to_string_lossy();
+    let n = TEMP_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let tmp = dir.join(format!(".{}.{}.{}.tmp", name, std::process::id(), n));
+
+    let written = File::create(&tmp)
+        .and_then(|mut f| {
+            f.write_all(bytes)?;
+            f.sync_all()
+        })
+        .and_then(|_| std::fs::rename(&tmp, path));
+    if let Err(e) = written {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(format!("failed to write '{}': {}", path.display(), e));
+    }
+
+    // make the rename itself durable
+    #[cfg(unix)]
+    if let Ok(d) = File::open(&dir) {
+        let _ = d.sync_all();
+    }
+    Ok(())
+}
 
 // function to turn "~/...." filepath into proper path
 fn expand_tilde(path: &str) -> Result<PathBuf, String>{
@@ -21,23 +57,21 @@ fn expand_tilde(path: &str) -> Result<PathBuf, String>{
     Err("something weird happened".to_string())
 }
 
-fn process_file(user_path: String) -> Result<String, String>
-{
-    let full_path;
-    let full_string;
-    if (user_path.chars().next().unwrap() != '~') { 
-        if (user_path.len() > 0)
-        {
-            full_string = ["~/", "salieri_files/", &user_path].join("");
-            full_path = expand_tilde(&full_string)?;
-        }
-        else {
-            return Err(format!("no good!"));
-        }
+// "~/..." is taken as is; anything else lives under ~/salieri_files/
+pub(crate) fn resolve_path(user_path: &str) -> Result<PathBuf, String> {
+    if user_path.is_empty() {
+        return Err("no good!".to_string());
     }
-    else {
-        full_path = expand_tilde(&user_path)?;
+    if user_path.starts_with('~') {
+        expand_tilde(user_path)
+    } else {
+        expand_tilde(&["~/", "salieri_files/", user_path].join(""))
     }
+}
+
+fn process_file(user_path: String) -> Result<String, String>
+{
+    let full_path = resolve_path(&user_path)?;
     
     if !full_path.exists() {
         let path = std::path::Path::new(&full_path);
@@ -58,21 +92,7 @@ pub async fn command_code(path: &[&str], _app: AppHandle) -> Result<String, Stri
 
 #[tauri::command]
 pub async fn save_file(user_path: String, information: String) -> Result<String, String> {
-    let actual_path;
-    let full_string;
-    if (user_path.chars().next().unwrap() != '~') { 
-        if (user_path.len() > 0)
-        {
-            full_string = ["~/", "salieri_files/", &user_path].join("");
-            actual_path = expand_tilde(&full_string)?;
-        }
-        else {
-            return Err(format!("no good!"));
-        }
-    }
-    else {
-        actual_path = expand_tilde(&user_path)?;
-    }
+    let actual_path = resolve_path(&user_path)?;
 
     // create parent directories if they don't exist
     if let Some(parent) = actual_path.parent() {
@@ -80,14 +100,7 @@ pub async fn save_file(user_path: String, information: String) -> Result<String,
             .map_err(|e| format!("failed to create directories: {}", e))?;
     }
     
-    let mut file = File::create(&actual_path)
-        .map_err(|e| format!("failed to create/open file '{}': {}", actual_path.display(), e))?;
-    
-    file.write_all(information.as_bytes())
-        .map_err(|e| format!("failed to write to file '{}': {}", actual_path.display(), e))?;
-    
-    file.flush()
-        .map_err(|e| format!("failed to flush file '{}': {}", actual_path.display(), e))?;
+    write_atomic(&actual_path, information.as_bytes())?;
     
     Ok(format!("file '{}' saved successfully.", actual_path.display()))
 }
\ No newline at end of file
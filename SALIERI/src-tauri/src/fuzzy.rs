@@ -0,0 +1,102 @@
+// small fuzzy matcher for task titles. scores run from 0.0 (nothing alike)
+// to 1.0 (same text, ignoring case).
+
+// optimal string alignment distance: levenshtein plus swapped neighbours,
+// so "lgoin" is one edit away from "login"
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let (n, m) = (a.len(), b.len());
+    let mut d = vec![vec![0usize; m + 1]; n + 1];
+    for (i, row) in d.iter_mut().enumerate() { row[0] = i; }
+    for (j, cell) in d[0].iter_mut().enumerate() { *cell = j; }
+
+    for i in 1..=n {
+        for j in 1..=m {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            d[i][j] = (d[i - 1][j] + 1).min(d[i][j - 1] + 1).min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[n][m]
+}
+
+fn ratio(a: &[char], b: &[char]) -> f64 {
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+    1.0 - edit_distance(a, b) as f64 / longest as f64
+}
+
+pub fn similarity(query: &str, text: &str) -> f64 {
+    let q: Vec<char> = query.to_lowercase().chars().collect();
+    let t: Vec<char> = text.to_lowercase().chars().collect();
+    if q.is_empty() || t.is_empty() {
+        return 0.0;
+    }
+
+    let whole = ratio(&q, &t);
+
+    // a short query against a long title: compare it with each run of words
+    // the same length as the query, and count that slightly less than a full match
+    let q_words = query.split_whitespace().count().max(1);
+    let t_words: Vec<&str> = text.split_whitespace().collect();
+    let mut best_window = 0.0f64;
+    if t_words.len() > q_words {
+        for w in t_words.windows(q_words) {
+            let win: Vec<char> = w.join(" ").to_lowercase().chars().collect();
+            best_window = best_window.max(ratio(&q, &win) * 0.95);
+        }
+    }
+
+    whole.max(best_window)
+}
+
+// every candidate scored against `query`, best first
+pub fn rank<T: Clone>(query: &str, candidates: &[(T, String)]) -> Vec<(T, f64)> {
+    let mut scored: Vec<(T, f64)> = candidates.iter()
+        .map(|(key, text)| (key.clone(), similarity(query, text)))
+        .collect();
+    scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    scored
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_text_ignoring_case_is_a_full_match() {
+        assert_eq!(similarity("Call Bob", "call bob"), 1.0);
+    }
+
+    #[test]
+    fn empty_text_matches_nothing() {
+        assert_eq!(similarity("", "call bob"), 0.0);
+        assert_eq!(similarity("call bob", ""), 0.0);
+    }
+
+    #[test]
+    fn swapped_letters_are_one_edit() {
+        let lgoin: Vec<char> = "lgoin".chars().collect();
+        let login: Vec<char> = "login".chars().collect();
+        assert_eq!(edit_distance(&lgoin, &login), 1);
+        assert!((similarity("lgoin", "login") - 0.8).abs() < 1e-9);
+    }
+
+    #[test]
+    fn a_word_inside_a_longer_title_scores_just_under_full() {
+        assert!((similarity("login", "fix login bug") - 0.95).abs() < 1e-9);
+        assert!(similarity("login", "fix login bug") > similarity("login", "logout"));
+    }
+
+    #[test]
+    fn rank_puts_the_best_first() {
+        let candidates = vec![(1, "write report".to_string()), (2, "fix login bug".to_string()), (3, "call bob".to_string())];
+        let ranked = rank("fix lgoin", &candidates);
+        assert_eq!(ranked.len(), 3);
+        assert_eq!(ranked[0].0, 2);
+        assert!(ranked.windows(2).all(|w| w[0].1 >= w[1].1));
+    }
+}
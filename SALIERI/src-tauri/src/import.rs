@@ -0,0 +1,336 @@
+// /import: tasks from todo.txt files and taskwarrior's `task export` json
+use chrono::{Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use serde::Deserialize;
+use tauri::AppHandle;
+
+use crate::fileaccess::resolve_path;
+use crate::labels::{self, Labels};
+use crate::tasks::{add_imported, get_current_logical_day_key, logical_day_of, ImportSkip, LogicalDay, Task, TaskStatus};
+
+// skipped tasks listed by name before the reply just gives a count
+const SKIPS_SHOWN: usize = 10;
+
+// tasks read from a file, with the lines that couldn't be
+type Parsed = (Vec<(LogicalDay, Task)>, Vec<String>);
+
+fn new_task(title: String, status: TaskStatus, created_at: LogicalDay, labels: Labels) -> Task {
+    Task { status, ..Task::new(title, created_at).with_labels(labels) }
+}
+
+// todo.txt's (A)..(C) and taskwarrior's H/M/L; anything lower isn't a priority here
+fn letter_priority(letter: &str) -> Option<u8> {
+    match letter {
+        "A" | "H" => Some(1),
+        "B" | "M" => Some(2),
+        "C" | "L" => Some(3),
+        _ => None,
+    }
+}
+
+fn date_key(s: &str) -> Option<LogicalDay> {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").ok().map(|d| d.format("%Y-%m-%d").to_string())
+}
+
+// ─── todo.txt
+// "@phone" reads as the tag #phone
+fn context(word: &str) -> Option<String> {
+    word.strip_prefix('@').and_then(|c| labels::tag(&format!("#{}", c)))
+}
+
+// "x 2025-06-03 2025-06-01 (A) call bob +home due:2025-06-02": done marker,
+// completion date, creation date and priority are all optional. the task goes
+// on its due date, else the day it was finished, else the day it was created.
+fn parse_todotxt_line(line: &str, today: &str) -> Option<(LogicalDay, Task)> {
+    let mut words = line.split_whitespace().peekable();
+    let done = words.next_if_eq(&"x").is_some();
+    let is_priority = |w: &&str| w.len() == 3 && w.starts_with('(') && w.ends_with(')') && w.as_bytes()[1].is_ascii_uppercase();
+    let mut pri = None;
+    if !done {
+        pri = words.next_if(is_priority);
+    }
+    let mut dates = Vec::new();
+    while dates.len() < if done { 2 } else { 1 } {
+        match words.peek().and_then(|w| date_key(w)) {
+            Some(d) => { dates.push(d); words.next(); }
+            None => break,
+        }
+    }
+    // a finished task lists when it was done before when it was made
+    let (completed, created) = match (done, dates.len()) {
+        (true, 2) => (Some(dates[0].clone()), Some(dates[1].clone())),
+        (true, 1) => (Some(dates[0].clone()), None),
+        (false, 1) => (None, Some(dates[0].clone())),
+        _ => (None, None),
+    };
+    pri = pri.or(words.next_if(is_priority));
+
+    let mut due = None;
+    let mut title = Vec::new();
+    for w in words {
+        match w.strip_prefix("due:").and_then(date_key) {
+            Some(d) => due = Some(d),
+            // completed tasks keep their priority as pri:A
+            None if done && w.starts_with("pri:") => pri = Some(&w[4..]),
+            None => title.push(w),
+        }
+    }
+    // todo.txt's +project is ours too, and its @contexts become tags
+    let (title, mut labels) = labels::extract_labels(&title);
+    let (contexts, title): (Vec<&str>, Vec<&str>) = title.into_iter().partition(|w| context(w).is_some());
+    for c in contexts.into_iter().filter_map(context) {
+        if !labels.tags.contains(&c) {
+            labels.tags.push(c);
+        }
+    }
+    if let Some(p) = pri.and_then(|p| letter_priority(p.trim_matches(['(', ')']))) {
+        labels.priority = Some(p);
+    }
+    if title.is_empty() {
+        return None;
+    }
+
+    let day = due.or(completed).or_else(|| created.clone()).unwrap_or_else(|| today.to_string());
+    let status = if done { TaskStatus::Done } else { TaskStatus::Todo };
+    let created_at = created.unwrap_or_else(|| day.clone());
+    Some((day, new_task(title.join(" "), status, created_at, labels)))
+}
+
+fn parse_todotxt(text: &str, today: &str) -> Parsed {
+    let mut tasks = Vec::new();
+    let mut unreadable = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match parse_todotxt_line(line, today) {
+            Some(t) => tasks.push(t),
+            None => unreadable.push(format!("line {}", i + 1)),
+        }
+    }
+    (tasks, unreadable)
+}
+
+// ─── taskwarrior
+#[derive(Deserialize)]
+struct TwTask {
+    description: String,
+    status: String,
+    #[serde(default)]
+    entry: Option<String>,
+    #[serde(default)]
+    due: Option<String>,
+    #[serde(default)]
+    end: Option<String>,
+    #[serde(default)]
+    project: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    priority: Option<String>,
+}
+
+// taskwarrior writes utc stamps like 20250601T120000Z
+fn tw_day(stamp: &str) -> Option<LogicalDay> {
+    let utc = NaiveDateTime::parse_from_str(stamp, "%Y%m%dT%H%M%SZ").ok()?;
+    Some(logical_day_of(Utc.from_utc_datetime(&utc).with_timezone(&Local)))
+}
+
+// newer versions export one json array; older ones one object per line
+fn read_taskwarrior(text: &str) -> Result<(Vec<TwTask>, Vec<String>), String> {
+    if text.trim_start().starts_with('[') {
+        let all = serde_json::from_str(text).map_err(|e| format!("not a taskwarrior export: {}", e))?;
+        return Ok((all, Vec::new()));
+    }
+    let mut all = Vec::new();
+    let mut unreadable = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim().trim_end_matches(',');
+        if line.is_empty() {
+            continue;
+        }
+        match serde_json::from_str(line) {
+            Ok(t) => all.push(t),
+            Err(_) => unreadable.push(format!("line {}", i + 1)),
+        }
+    }
+    Ok((all, unreadable))
+}
+
+fn parse_taskwarrior(text: &str, today: &str) -> Result<Parsed, String> {
+    let (all, unreadable) = read_taskwarrior(text)?;
+    let mut tasks = Vec::new();
+    for tw in all {
+        let status = match tw.status.as_str() {
+            "pending" | "waiting" => TaskStatus::Todo,
+            "completed" => TaskStatus::Done,
+            // deleted tasks and the templates behind recurring ones
+            _ => continue,
+        };
+        let title = tw.description.split_whitespace().collect::<Vec<_>>().join(" ");
+        if title.is_empty() {
+            continue;
+        }
+        let created = tw.entry.as_deref().and_then(tw_day);
+        let finished = tw.end.as_deref().filter(|_| status == TaskStatus::Done).and_then(tw_day);
+        let day = tw.due.as_deref().and_then(tw_day)
+            .or(finished)
+            .or_else(|| created.clone())
+            .unwrap_or_else(|| today.to_string());
+        let created_at = created.unwrap_or_else(|| day.clone());
+        let labels = Labels {
+            tags: tw.tags.iter().filter_map(|t| labels::tag(&format!("#{}", t))).collect(),
+            project: tw.project.as_deref().and_then(|p| labels::project(&format!("+{}", p))),
+            priority: tw.priority.as_deref().and_then(letter_priority),
+            estimate: None,
+        };
+        tasks.push((day, new_task(title, status, created_at, labels)));
+    }
+    Ok((tasks, unreadable))
+}
+
+// ─── /import todotxt|taskwarrior <path>
+pub async fn command_import(parts: &[&str], _app: AppHandle) -> Result<String, String> {
+    let usage = "usage: /import todotxt|taskwarrior <path>";
+    let (Some(kind), true) = (parts.get(1), parts.len() > 2) else { return Err(usage.into()) };
+    let path = resolve_path(&parts[2..].join(" "))?;
+    let text = std::fs::read_to_string(&path).map_err(|e| format!("couldn't read {}: {}", path.display(), e))?;
+
+    let today = get_current_logical_day_key(None);
+    let (tasks, unreadable) = match *kind {
+        "todotxt" | "todo.txt" => parse_todotxt(&text, &today),
+        "taskwarrior" | "tw" => parse_taskwarrior(&text, &today)?,
+        other => return Err(format!("unknown import format '{}'. use todotxt or taskwarrior", other)),
+    };
+    if tasks.is_empty() && unreadable.is_empty() {
+        return Err(format!("no tasks found in {}", path.display()));
+    }
+
+    let total = tasks.len();
+    let skipped = add_imported(tasks).await?;
+    let mut out = vec![format!("imported {} of {} task(s) from {}", total - skipped.len(), total, path.display())];
+
+    let duplicates: Vec<String> = skipped.iter()
+        .filter(|(_, _, why)| matches!(why, ImportSkip::Duplicate))
+        .map(|(day, title, _)| format!("'{}' on {}", title, day))
+        .collect();
+    let over_limit: Vec<String> = skipped.iter()
+        .filter(|(_, _, why)| matches!(why, ImportSkip::OverLimit))
+        .map(|(day, title, _)| format!("'{}' on {}", title, day))
+        .collect();
+    for (label, list) in [("already there", &duplicates), ("over the daily limit", &over_limit), ("unreadable", &unreadable)] {
+        if list.is_empty() {
+            continue;
+        }
+        let mut line = format!("{} {}: {}", list.len(), label, list.iter().take(SKIPS_SHOWN).cloned().collect::<Vec<_>>().join(", "));
+        if list.len() > SKIPS_SHOWN {
+            line.push_str(&format!(" and {} more", list.len() - SKIPS_SHOWN));
+        }
+        out.push(line);
+    }
+    Ok(out.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TODAY: &str = "2025-06-04";
+
+    fn line(s: &str) -> (LogicalDay, Task) {
+        parse_todotxt_line(s, TODAY).expect(s)
+    }
+
+    #[test]
+    fn todotxt_priority_and_creation_date() {
+        let (day, t) = line("(B) 2025-06-01 write report @phone +work");
+        assert_eq!(day, "2025-06-01");
+        assert_eq!(t.title, "write report");
+        assert_eq!(t.status, TaskStatus::Todo);
+        assert_eq!(t.priority, Some(2));
+        assert_eq!(t.tags, vec!["phone"]);
+        assert_eq!(t.project.as_deref(), Some("work"));
+        assert_eq!(t.created_at, "2025-06-01");
+    }
+
+    #[test]
+    fn todotxt_priority_after_the_date_and_past_c() {
+        assert_eq!(line("2025-06-01 (A) call bob").1.priority, Some(1));
+        let (_, t) = line("(D) someday");
+        assert_eq!(t.priority, None);
+        assert_eq!(t.title, "someday");
+    }
+
+    #[test]
+    fn todotxt_due_date_wins() {
+        let (day, t) = line("2025-06-01 pay rent due:2025-06-10");
+        assert_eq!(day, "2025-06-10");
+        assert_eq!(t.title, "pay rent");
+        assert_eq!(t.created_at, "2025-06-01");
+    }
+
+    #[test]
+    fn todotxt_done_rows() {
+        let (day, t) = line("x 2025-06-03 2025-06-01 call bob pri:A");
+        assert_eq!(day, "2025-06-03");
+        assert_eq!(t.status, TaskStatus::Done);
+        assert_eq!(t.priority, Some(1));
+        assert_eq!(t.title, "call bob");
+        assert_eq!(t.created_at, "2025-06-01");
+
+        let (day, t) = line("x 2025-06-03 tidy desk");
+        assert_eq!(day, "2025-06-03");
+        assert_eq!(t.created_at, "2025-06-03");
+    }
+
+    #[test]
+    fn todotxt_undated_goes_on_today_and_blank_titles_are_skipped() {
+        assert_eq!(line("water plants").0, TODAY);
+        assert!(parse_todotxt_line("(A)", TODAY).is_none());
+        let (tasks, unreadable) = parse_todotxt("water plants\n\n(A) 2025-06-01\n", TODAY);
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(unreadable, vec!["line 3"]);
+    }
+
+    const TW: &str = r#"[
+        {"description": "write report", "status": "pending", "entry": "20250601T120000Z", "priority": "H", "project": "Work", "tags": ["desk"]},
+        {"description": "call bob", "status": "completed", "entry": "20250601T120000Z", "end": "20250603T120000Z", "priority": "L"},
+        {"description": "old idea", "status": "deleted", "entry": "20250601T120000Z"},
+        {"description": "pay rent", "status": "waiting", "due": "20250610T120000Z", "priority": "M"}
+    ]"#;
+
+    #[test]
+    fn taskwarrior_statuses_and_priorities() {
+        let (tasks, unreadable) = parse_taskwarrior(TW, TODAY).unwrap();
+        assert!(unreadable.is_empty());
+        let titles: Vec<&str> = tasks.iter().map(|(_, t)| t.title.as_str()).collect();
+        assert_eq!(titles, vec!["write report", "call bob", "pay rent"]);
+
+        let (_, report) = &tasks[0];
+        assert_eq!(report.status, TaskStatus::Todo);
+        assert_eq!(report.priority, Some(1));
+        assert_eq!(report.project.as_deref(), Some("work"));
+        assert_eq!(report.tags, vec!["desk"]);
+        assert_eq!(tasks[1].1.status, TaskStatus::Done);
+        assert_eq!(tasks[1].1.priority, Some(3));
+        assert_eq!(tasks[2].1.priority, Some(2));
+    }
+
+    #[test]
+    fn taskwarrior_days() {
+        let (tasks, _) = parse_taskwarrior(TW, TODAY).unwrap();
+        // created, finished, and due
+        assert_eq!(tasks[0].0, tw_day("20250601T120000Z").unwrap());
+        assert_eq!(tasks[1].0, tw_day("20250603T120000Z").unwrap());
+        assert_eq!(tasks[1].1.created_at, tw_day("20250601T120000Z").unwrap());
+        assert_eq!(tasks[2].0, tw_day("20250610T120000Z").unwrap());
+    }
+
+    #[test]
+    fn taskwarrior_one_object_per_line() {
+        let text = "{\"description\": \"a\", \"status\": \"pending\"},\nnot json\n";
+        let (tasks, unreadable) = parse_taskwarrior(text, TODAY).unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].0, TODAY);
+        assert_eq!(unreadable, vec!["line 2"]);
+    }
+}
@@ -0,0 +1,354 @@
+// append-only record of every change to the task store.
+//
+// journal.jsonl holds one event per line since the last compaction. at launch
+// the store is the backend's snapshot with the journal replayed on top.
+// compaction writes a fresh snapshot and moves the events into
+// journal/<yyyy-mm>.jsonl, which is kept for task history.
+//
+// events carry the task as it was after the change, so replaying one that the
+// snapshot already includes is harmless.
+use std::{collections::BTreeSet, fs::{self, OpenOptions}, io::Write, path::{Path, PathBuf}, sync::Mutex};
+use chrono::{DateTime, Local};
+use lazy_static::lazy_static;
+use serde::{Serialize, Deserialize};
+
+use crate::storage::{self, data_dir};
+use crate::tasks::{DayBucket, LogicalDay, Store, Task, TaskId, TaskStatus};
+
+// events in journal.jsonl before the timer loop compacts it
+const COMPACT_AFTER: usize = 500;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "lowercase")]
+pub enum Change {
+    Created { task: Task },
+    Started { task: Task },
+    Paused { task: Task },
+    Completed { task: Task },
+    Deleted { id: TaskId, title: String },
+    Renamed { task: Task, old_title: String },
+    Reopened { task: Task },
+    // an item added to the task's checklist, or checked off
+    Checklist { task: Task },
+    // the tasks it waits on changed: /block, /unblock, or a blocker finishing
+    Blockers { task: Task },
+    // `day` is where the task went
+    Moved { task: Task, from: LogicalDay },
+    Ticked { id: TaskId, time_spent: u64 },
+    // the whole day at once, for changes like rollover or /undo
+    Replaced { bucket: Option<DayBucket> },
+}
+
+impl Change {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Change::Created { .. } => "created",
+            Change::Started { .. } => "started",
+            Change::Paused { .. } => "paused",
+            Change::Completed { .. } => "completed",
+            Change::Deleted { .. } => "deleted",
+            Change::Renamed { .. } => "renamed",
+            Change::Reopened { .. } => "reopened",
+            Change::Checklist { .. } => "checklist",
+            Change::Blockers { .. } => "blockers",
+            Change::Moved { .. } => "moved",
+            Change::Ticked { .. } => "ticked",
+            Change::Replaced { .. } => "replaced",
+        }
+    }
+
+    pub fn task_id(&self) -> Option<&str> {
+        match self {
+            Change::Created { task } | Change::Started { task }
+            | Change::Paused { task } | Change::Completed { task }
+            | Change::Renamed { task, .. } | Change::Reopened { task }
+            | Change::Checklist { task } | Change::Blockers { task }
+            | Change::Moved { task, .. } => Some(&task.id),
+            Change::Deleted { id, .. } | Change::Ticked { id, .. } => Some(id),
+            Change::Replaced { .. } => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Event {
+    pub at: DateTime<Local>,
+    pub day: LogicalDay,
+    #[serde(flatten)]
+    pub change: Change,
+}
+
+struct JournalState {
+    // events written since the last compaction
+    pending: usize,
+    // days those events touched; the only ones an incremental backend has to rewrite
+    dirty: BTreeSet<LogicalDay>,
+}
+
+lazy_static! {
+    static ref JOURNAL: Mutex<JournalState> = Mutex::new(JournalState { pending: 0, dirty: BTreeSet::new() });
+}
+
+pub fn journal_path() -> PathBuf { data_dir().join("journal.jsonl") }
+
+// events being folded into a snapshot; replayed too if a compaction was cut short
+fn compacting_path() -> PathBuf { data_dir().join("journal.compacting.jsonl") }
+
+fn archive_dir() -> PathBuf { data_dir().join("journal") }
+
+// files a backup has to carry along with the snapshot
+pub fn journal_files() -> Vec<PathBuf> { vec![compacting_path(), journal_path()] }
+
+fn append_bytes(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let mut f = OpenOptions::new().create(true).append(true).open(path)
+        .map_err(|e| format!("failed to open {}: {}", path.display(), e))?;
+    f.write_all(bytes).and_then(|_| f.sync_data())
+        .map_err(|e| format!("failed to write {}: {}", path.display(), e))
+}
+
+// writes one event on a blocking thread and hands it back once it's on disk.
+// callers apply it to the store only then (see tasks::commit), and hold the
+// store lock across the write so events land in the order they happened
+pub async fn append(day: &LogicalDay, change: Change) -> Result<Event, String> {
+    storage::ensure_writable()?;
+    let ev = Event { at: Local::now(), day: day.clone(), change };
+    let mut line = serde_json::to_string(&ev).map_err(|e| e.to_string())?;
+    line.push('\n');
+
+    let day = day.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut j = JOURNAL.lock().unwrap();
+        append_bytes(&journal_path(), line.as_bytes())?;
+        j.pending += 1;
+        j.dirty.insert(day);
+        Ok::<_, String>(())
+    })
+    .await
+    .map_err(|e| format!("Failed to join journal write: {}", e))??;
+    Ok(ev)
+}
+
+// ─── replay
+fn upsert(bucket: &mut DayBucket, task: &Task) {
+    let (list, other) = if task.status == TaskStatus::Done {
+        (&mut bucket.done, &mut bucket.todo)
+    } else {
+        (&mut bucket.todo, &mut bucket.done)
+    };
+    other.shift_remove(&task.id);
+    match list.get_mut(&task.id) {
+        Some(t) => *t = task.clone(),
+        None => { list.insert(task.id.clone(), task.clone()); }
+    }
+}
+
+pub fn apply(st: &mut Store, ev: &Event) {
+    let day = &ev.day;
+    match &ev.change {
+        Change::Created { task } | Change::Started { task }
+        | Change::Paused { task } | Change::Completed { task }
+        | Change::Renamed { task, .. } | Change::Reopened { task }
+        | Change::Checklist { task } | Change::Blockers { task } => {
+            upsert(st.entry(day.clone()).or_default(), task);
+        }
+        Change::Moved { task, from } => {
+            if let Some(b) = st.get_mut(from) {
+                b.todo.shift_remove(&task.id);
+                b.done.shift_remove(&task.id);
+            }
+            upsert(st.entry(day.clone()).or_default(), task);
+        }
+        Change::Deleted { id, .. } => {
+            if let Some(b) = st.get_mut(day) {
+                b.todo.shift_remove(id);
+                b.done.shift_remove(id);
+            }
+        }
+        Change::Ticked { id, time_spent } => {
+            if let Some(b) = st.get_mut(day) {
+                if let Some(t) = b.todo.get_mut(id) {
+                    t.time_spent = *time_spent;
+                } else if let Some(t) = b.done.get_mut(id) {
+                    t.time_spent = *time_spent;
+                }
+            }
+        }
+        Change::Replaced { bucket: Some(b) } => { st.insert(day.clone(), b.clone()); }
+        Change::Replaced { bucket: None } => { st.shift_remove(day); }
+    }
+}
+
+// a crash mid-write can leave the last line cut off; anything else unreadable is an error
+fn read_events(path: &Path) -> Result<Vec<Event>, String> {
+    let text = match fs::read_to_string(path) {
+        Ok(t) => t,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("failed to read {}: {}", path.display(), e)),
+    };
+    let lines: Vec<&str> = text.lines().filter(|l| !l.trim().is_empty()).collect();
+    let mut events = Vec::with_capacity(lines.len());
+    for (i, line) in lines.iter().enumerate() {
+        match serde_json::from_str(line) {
+            Ok(ev) => events.push(ev),
+            Err(e) if i + 1 == lines.len() => eprintln!("ignoring torn last line of {}: {}", path.display(), e),
+            Err(e) => return Err(format!("{} line {} is unreadable: {}", path.display(), i + 1, e)),
+        }
+    }
+    Ok(events)
+}
+
+// plays the journal over a snapshot. returns how many events were applied.
+pub fn replay(st: &mut Store) -> Result<usize, String> {
+    let mut count = 0;
+    let mut j = JOURNAL.lock().unwrap();
+    j.pending = 0;
+    j.dirty.clear();
+    for path in journal_files() {
+        for ev in read_events(&path)? {
+            apply(st, &ev);
+            j.pending += 1;
+            j.dirty.insert(ev.day.clone());
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+// ─── compaction
+pub fn needs_compaction() -> bool {
+    JOURNAL.lock().unwrap().pending >= COMPACT_AFTER
+}
+
+// what a compaction took from the journal state, handed back if the snapshot
+// couldn't be saved
+pub struct Compaction {
+    // the days the snapshot has to rewrite
+    pub days: Vec<LogicalDay>,
+    pending: usize,
+}
+
+// sets the journal aside so new events start a fresh file. returns None when
+// there's nothing to compact. the caller must hold the task store lock until
+// it has copied the store, then call end_compaction with how saving it went.
+pub fn begin_compaction() -> Result<Option<Compaction>, String> {
+    begin_in(&mut JOURNAL.lock().unwrap(), &journal_path(), &compacting_path())
+}
+
+fn begin_in(j: &mut JournalState, current: &Path, compacting: &Path) -> Result<Option<Compaction>, String> {
+    if j.pending == 0 {
+        return Ok(None);
+    }
+    if current.exists() {
+        // an earlier compaction that didn't finish keeps its file; add to it
+        let bytes = fs::read(current).map_err(|e| format!("failed to read {}: {}", current.display(), e))?;
+        append_bytes(compacting, &bytes)?;
+        fs::remove_file(current).map_err(|e| format!("failed to clear {}: {}", current.display(), e))?;
+    }
+    let pending = std::mem::take(&mut j.pending);
+    Ok(Some(Compaction { days: std::mem::take(&mut j.dirty).into_iter().collect(), pending }))
+}
+
+// once the snapshot is safely written, the set-aside events go to the archive.
+// if it wasn't, they stay set aside and their days are still owed, so the next
+// compaction rewrites them along with whatever changed since
+pub fn end_compaction(c: Compaction, saved: Result<(), String>) -> Result<(), String> {
+    let archive = archive_dir().join(format!("{}.jsonl", Local::now().format("%Y-%m")));
+    end_in(&mut JOURNAL.lock().unwrap(), c, saved, &compacting_path(), &archive)
+}
+
+fn end_in(j: &mut JournalState, c: Compaction, saved: Result<(), String>, compacting: &Path, archive: &Path) -> Result<(), String> {
+    if let Err(e) = saved {
+        j.pending += c.pending;
+        j.dirty.extend(c.days);
+        return Err(e);
+    }
+    if !compacting.exists() {
+        return Ok(());
+    }
+    let bytes = fs::read(compacting).map_err(|e| format!("failed to read {}: {}", compacting.display(), e))?;
+    append_bytes(archive, &bytes)?;
+    fs::remove_file(compacting).map_err(|e| format!("failed to clear {}: {}", compacting.display(), e))
+}
+
+// ─── history
+// every event about one task, oldest first, from the archive and the live journal
+pub fn task_history(id: &str) -> Result<Vec<Event>, String> {
+    let mut files: Vec<PathBuf> = fs::read_dir(archive_dir())
+        .map(|entries| entries.filter_map(|e| e.ok()).map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|x| x == "jsonl"))
+            .collect())
+        .unwrap_or_default();
+    files.sort();
+    files.extend(journal_files());
+
+    let mut events = Vec::new();
+    for path in files {
+        events.extend(read_events(&path)?.into_iter().filter(|ev| ev.change.task_id() == Some(id)));
+    }
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("salieri-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write(j: &mut JournalState, path: &Path, day: &str) {
+        append_bytes(path, format!("{{\"day\":\"{}\"}}\n", day).as_bytes()).unwrap();
+        j.pending += 1;
+        j.dirty.insert(day.to_string());
+    }
+
+    fn lines(path: &Path) -> usize {
+        fs::read_to_string(path).map(|t| t.lines().count()).unwrap_or(0)
+    }
+
+    #[test]
+    fn nothing_to_compact() {
+        let dir = scratch();
+        let mut j = JournalState { pending: 0, dirty: BTreeSet::new() };
+        assert!(begin_in(&mut j, &dir.join("journal.jsonl"), &dir.join("compacting.jsonl")).unwrap().is_none());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn failed_save_keeps_its_days_for_the_next_compaction() {
+        let dir = scratch();
+        let (live, compacting, archive) = (dir.join("journal.jsonl"), dir.join("compacting.jsonl"), dir.join("archive.jsonl"));
+        let mut j = JournalState { pending: 0, dirty: BTreeSet::new() };
+        write(&mut j, &live, "2025-06-01");
+        write(&mut j, &live, "2025-06-01");
+
+        let c = begin_in(&mut j, &live, &compacting).unwrap().unwrap();
+        assert_eq!(c.days, vec!["2025-06-01"]);
+        assert!(!live.exists());
+        assert_eq!(lines(&compacting), 2);
+
+        // the backend couldn't save the snapshot
+        let err = end_in(&mut j, c, Err("disk full".into()), &compacting, &archive).unwrap_err();
+        assert_eq!(err, "disk full");
+        assert_eq!(j.pending, 2);
+        assert_eq!(lines(&compacting), 2);
+        assert!(!archive.exists());
+
+        // the next one rewrites the first day too, and archives everything once saved
+        write(&mut j, &live, "2025-06-02");
+        let c = begin_in(&mut j, &live, &compacting).unwrap().unwrap();
+        assert_eq!(c.days, vec!["2025-06-01", "2025-06-02"]);
+        assert_eq!(lines(&compacting), 3);
+        end_in(&mut j, c, Ok(()), &compacting, &archive).unwrap();
+        assert_eq!(j.pending, 0);
+        assert!(j.dirty.is_empty());
+        assert!(!compacting.exists());
+        assert_eq!(lines(&archive), 3);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,162 @@
+// the extra words a /todo line can carry: #tags, a +project, a !1..!3
+// priority and a ~45m or ~2p estimate. tags and projects need a letter first
+// so "issue #12" and the "+3d" date offset stay part of the title.
+use crate::pomodoro::WORK_SECS;
+
+#[derive(Debug, Clone, Default)]
+pub struct Labels {
+    pub tags: Vec<String>,
+    pub project: Option<String>,
+    pub priority: Option<u8>,
+    // seconds
+    pub estimate: Option<u64>,
+}
+
+fn label(word: &str, sigil: char) -> Option<&str> {
+    let name = word.strip_prefix(sigil)?;
+    let ok = name.chars().next().is_some_and(|c| c.is_alphabetic())
+        && name.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_' || c == '/');
+    ok.then_some(name)
+}
+
+pub fn tag(word: &str) -> Option<String> {
+    label(word, '#').map(|t| t.to_lowercase())
+}
+
+pub fn project(word: &str) -> Option<String> {
+    label(word, '+').map(|p| p.to_lowercase())
+}
+
+// !1 is the most urgent
+pub fn priority(word: &str) -> Option<u8> {
+    match word {
+        "!1" => Some(1),
+        "!2" => Some(2),
+        "!3" => Some(3),
+        _ => None,
+    }
+}
+
+// ~45m, ~2h, ~1h30m, or ~2p for two pomodoros. None when it isn't one, or
+// is too big to count
+pub fn estimate(word: &str) -> Option<u64> {
+    let mut rest = word.strip_prefix('~')?;
+    let mut secs: u64 = 0;
+    while !rest.is_empty() {
+        let digits = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+        let n: u64 = rest[..digits].parse().ok()?;
+        let unit = match rest[digits..].chars().next()? {
+            'h' => 3600,
+            'm' => 60,
+            'p' => WORK_SECS,
+            _ => return None,
+        };
+        secs = secs.checked_add(n.checked_mul(unit)?)?;
+        rest = &rest[digits + 1..];
+    }
+    (secs > 0).then_some(secs)
+}
+
+// how an estimate is written back, e.g. "1h30m"
+pub fn format_estimate(secs: u64) -> String {
+    match (secs / 3600, (secs % 3600) / 60) {
+        (0, m) => format!("{}m", m),
+        (h, 0) => format!("{}h", h),
+        (h, m) => format!("{}h{}m", h, m),
+    }
+}
+
+// pulls the labels out of `words`, returning the title words left over.
+// tags keep their first-seen order without repeats; for the rest the last
+// one given wins. a title made only of labels is left alone.
+pub fn extract_labels<'a>(words: &[&'a str]) -> (Vec<&'a str>, Labels) {
+    let mut rest = Vec::new();
+    let mut labels = Labels::default();
+    for w in words {
+        if let Some(t) = tag(w) {
+            if !labels.tags.contains(&t) {
+                labels.tags.push(t);
+            }
+        } else if let Some(p) = project(w) {
+            labels.project = Some(p);
+        } else if let Some(p) = priority(w) {
+            labels.priority = Some(p);
+        } else if let Some(e) = estimate(w) {
+            labels.estimate = Some(e);
+        } else {
+            rest.push(*w);
+        }
+    }
+    if rest.is_empty() {
+        return (words.to_vec(), Labels::default());
+    }
+    (rest, labels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn extract(s: &str) -> (String, Labels) {
+        let words: Vec<&str> = s.split_whitespace().collect();
+        let (rest, labels) = extract_labels(&words);
+        (rest.join(" "), labels)
+    }
+
+    #[test]
+    fn labels_come_out_of_the_title() {
+        let (title, l) = extract("call bob #Phone +Home !1 ~30m #phone #calls");
+        assert_eq!(title, "call bob");
+        assert_eq!(l.tags, vec!["phone", "calls"]);
+        assert_eq!(l.project.as_deref(), Some("home"));
+        assert_eq!(l.priority, Some(1));
+        assert_eq!(l.estimate, Some(30 * 60));
+    }
+
+    #[test]
+    fn issue_numbers_and_date_offsets_stay_in_the_title() {
+        let (title, l) = extract("fix issue #12 +3d");
+        assert_eq!(title, "fix issue #12 +3d");
+        assert!(l.tags.is_empty());
+        assert_eq!(l.project, None);
+    }
+
+    #[test]
+    fn a_title_of_only_labels_is_left_alone() {
+        let (title, l) = extract("#home !2");
+        assert_eq!(title, "#home !2");
+        assert!(l.tags.is_empty());
+        assert_eq!(l.priority, None);
+    }
+
+    #[test]
+    fn estimates() {
+        assert_eq!(estimate("~45m"), Some(45 * 60));
+        assert_eq!(estimate("~2h"), Some(2 * 3600));
+        assert_eq!(estimate("~1h30m"), Some(5400));
+        assert_eq!(estimate("~2p"), Some(2 * WORK_SECS));
+        assert_eq!(format_estimate(5400), "1h30m");
+    }
+
+    #[test]
+    fn not_estimates() {
+        for w in ["45m", "~", "~0m", "~h", "~5", "~5x", "~1h30"] {
+            assert_eq!(estimate(w), None, "{}", w);
+        }
+    }
+
+    #[test]
+    fn huge_estimates_are_refused() {
+        assert_eq!(estimate("~18446744073709551615h"), None);
+        assert_eq!(estimate("~99999999999999999999m"), None);
+        assert_eq!(estimate("~5124095576030431h5124095576030431h"), None);
+    }
+
+    #[test]
+    fn the_last_project_and_priority_win() {
+        let (_, l) = extract("plan +a !3 +b !2 trip");
+        assert_eq!(l.project.as_deref(), Some("b"));
+        assert_eq!(l.priority, Some(2));
+        assert_eq!(priority("!4"), None);
+    }
+}
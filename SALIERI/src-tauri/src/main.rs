@@ -6,12 +6,29 @@ mod pomodoro;
 mod tasks;
 mod commands;
 mod fileaccess;
+mod storage;
+mod backup;
+mod config;
+mod recurrence;
+mod fuzzy;
+mod dateparse;
+mod labels;
+mod undo;
+mod journal;
+mod sessions;
+mod reports;
+mod export;
+mod import;
 
 use crate::theme::{set_theme, get_current_theme, ThemeChangedPayload, THEME_KEY, DEFAULT_THEME, SETTINGS_STORE_FILENAME};
-use crate::tasks::{get_tasks, start_task_timer_loop, clear_active_startup, get_current_logical_day_key};
+use crate::tasks::{get_tasks, suggest_tasks, get_time_by_hour, get_interrupted_task, get_completed, search_tasks, get_blocked, start_task_timer_loop, clear_active_startup, rollover_startup, compact_store, get_current_logical_day_key};
 use crate::pomodoro::init_pomodoro;
 use crate::commands::handle_palette_command;
 use crate::fileaccess::save_file;
+use crate::backup::{create_backup, start_backup_loop};
+use crate::config::load_config;
+use crate::reports::get_report;
+use crate::storage::{select_backend, set_load_error, load_error, get_store_error, StorageKind, STORAGE_KEY};
 
 use serde_json::json;
 use tauri_plugin_store::StoreExt;
@@ -47,7 +64,42 @@ fn main() {
 
             println!("initial theme value: {}", theme_value);
 
+            // today_key and the daily limit read from this cache
+            if let Err(e) = load_config(&app_handle) {
+                eprintln!("couldn't load settings, using defaults: {}", e);
+            }
+
+            // pick the task backend before anything loads the task store
+            let storage_kind = store
+                .get(STORAGE_KEY)
+                .and_then(|v| v.as_str().and_then(StorageKind::parse))
+                .unwrap_or(StorageKind::Json);
+            if let Err(e) = select_backend(storage_kind) {
+                // keep the json store read-only rather than writing it behind the database's back
+                set_load_error(Some(format!("couldn't open {} task storage: {}", storage_kind.as_str(), e)));
+            }
+
+            // snapshot what's on disk before startup touches any of it
+            match create_backup(&app_handle) {
+                Ok(stamp) => println!("startup backup taken: {}", stamp),
+                Err(e) => eprintln!("startup backup failed: {}", e),
+            }
+            start_backup_loop(app_handle.clone());
+
             tauri::async_runtime::block_on(clear_active_startup(app_handle.clone()));
+            if let Err(e) = tauri::async_runtime::block_on(rollover_startup(app_handle.clone())) {
+                eprintln!("startup rollover failed: {}", e);
+            }
+            // fold the last session's journal into a fresh snapshot
+            if load_error().is_none() {
+                if let Err(e) = tauri::async_runtime::block_on(compact_store()) {
+                    eprintln!("startup journal compaction failed: {}", e);
+                }
+            }
+            if let Some(e) = load_error() {
+                eprintln!("task store unavailable: {}", e);
+                app.emit("store_error", e)?;
+            }
 
             app.emit("theme_changed", ThemeChangedPayload { theme: theme_value })?;
             tauri::async_runtime::block_on(init_pomodoro(app_handle.clone()));  
@@ -61,6 +113,14 @@ fn main() {
             get_tasks
             ,get_current_logical_day_key,
             save_file,
+            get_store_error,
+            suggest_tasks,
+            get_time_by_hour,
+            get_interrupted_task,
+            get_completed,
+            search_tasks,
+            get_report,
+            get_blocked,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
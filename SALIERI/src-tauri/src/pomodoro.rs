@@ -9,6 +9,9 @@ use tokio::sync::Mutex as TokioMutex;
 
 use crate::user::increment_pomodoros_done;
 
+// one pomodoro of work; ~2p estimates count in these
+pub const WORK_SECS: u64 = 60 * 25;
+
 #[derive(Clone, serde::Serialize)]
 pub struct TimerUpdatePayload {
     pub state: String,
@@ -45,7 +48,7 @@ pub struct PomodoroTimer {
 
 impl PomodoroTimer {
     pub fn new(app_handle: AppHandle) -> Self {
-        let work_secs = 60 * 25; 
+        let work_secs = WORK_SECS;
         let short_break_secs = 60 * 25; 
         let long_break_secs = 15 * 60; 
         let interval_time = 1;
@@ -0,0 +1,340 @@
+use std::sync::Mutex;
+use chrono::{Datelike, NaiveDate, Weekday};
+use lazy_static::lazy_static;
+use serde::{Serialize, Deserialize};
+use tauri::AppHandle;
+use uuid::Uuid;
+
+use crate::storage::{data_dir, load_json_or_quarantine, save_json};
+use crate::tasks::{get_current_logical_day_key, materialize_day, DayBucket, LogicalDay, Task};
+use crate::labels;
+
+// ─── rules
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "every", content = "on", rename_all = "lowercase")]
+pub enum Schedule {
+    Day,
+    Weekday,
+    Week(Vec<Weekday>),
+    // day of the month; months that are too short get it on their last day
+    Month(u32),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecurrenceRule {
+    pub id: String,
+    pub title: String,
+    pub schedule: Schedule,
+    #[serde(default)]
+    pub paused: bool,
+    // first logical day the rule applies to; earlier days are never backfilled
+    pub starts: LogicalDay,
+    // days (today or later) this rule has already put its task into
+    #[serde(default)]
+    pub applied: Vec<LogicalDay>,
+}
+
+lazy_static! {
+    static ref RULES: Mutex<Vec<RecurrenceRule>> = Mutex::new(load_rules());
+    // why recurrences.json couldn't be read. nothing is saved over it while set
+    static ref RULES_ERROR: Mutex<Option<String>> = Mutex::new(None);
+}
+
+pub fn rules_path() -> std::path::PathBuf { data_dir().join("recurrences.json") }
+
+fn load_rules() -> Vec<RecurrenceRule> {
+    let (rules, error) = match load_json_or_quarantine(&rules_path()) {
+        Ok(rules) => (rules, None),
+        Err(e) if e == "missing" => (Vec::new(), None),
+        Err(e) => {
+            eprintln!("recurrences.json failed to load: {}", e);
+            (Vec::new(), Some(e))
+        }
+    };
+    *RULES_ERROR.lock().unwrap() = error;
+    rules
+}
+
+// pick up a recurrences.json that was replaced on disk, e.g. by /restore
+pub fn reload_rules() {
+    *RULES.lock().unwrap() = load_rules();
+}
+
+fn save_rules(rules: &[RecurrenceRule]) -> Result<(), String> {
+    if let Some(e) = RULES_ERROR.lock().unwrap().clone() {
+        return Err(format!("recurring tasks can't be changed until this is fixed: {}", e));
+    }
+    save_json(&rules_path(), &rules)
+}
+
+fn parse_day(day: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(day, "%Y-%m-%d").ok()
+}
+
+fn last_day_of_month(d: NaiveDate) -> u32 {
+    let (y, m) = if d.month() == 12 { (d.year() + 1, 1) } else { (d.year(), d.month() + 1) };
+    NaiveDate::from_ymd_opt(y, m, 1).and_then(|n| n.pred_opt()).map(|n| n.day()).unwrap_or(28)
+}
+
+impl Schedule {
+    pub fn matches(&self, d: NaiveDate) -> bool {
+        match self {
+            Schedule::Day => true,
+            Schedule::Weekday => !matches!(d.weekday(), Weekday::Sat | Weekday::Sun),
+            Schedule::Week(days) => days.contains(&d.weekday()),
+            Schedule::Month(n) => d.day() == (*n).min(last_day_of_month(d)),
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Schedule::Day => "every day".into(),
+            Schedule::Weekday => "every weekday".into(),
+            Schedule::Week(days) => format!(
+                "every {}",
+                days.iter().map(|d| d.to_string().to_lowercase()).collect::<Vec<_>>().join(",")
+            ),
+            Schedule::Month(n) => format!("every month on the {}", ordinal(*n)),
+        }
+    }
+}
+
+fn ordinal(n: u32) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{}{}", n, suffix)
+}
+
+pub(crate) fn parse_weekday(s: &str) -> Option<Weekday> {
+    match s.to_lowercase().as_str() {
+        "mon" | "monday" => Some(Weekday::Mon),
+        "tue" | "tues" | "tuesday" => Some(Weekday::Tue),
+        "wed" | "weds" | "wednesday" => Some(Weekday::Wed),
+        "thu" | "thur" | "thurs" | "thursday" => Some(Weekday::Thu),
+        "fri" | "friday" => Some(Weekday::Fri),
+        "sat" | "saturday" => Some(Weekday::Sat),
+        "sun" | "sunday" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+// "day", "weekday", "weekend", "mon,thu", "month", "15th"
+fn parse_schedule(spec: &str, today: NaiveDate) -> Option<Schedule> {
+    match spec.to_lowercase().as_str() {
+        "day" | "daily" => return Some(Schedule::Day),
+        "weekday" | "weekdays" => return Some(Schedule::Weekday),
+        "weekend" | "weekends" => return Some(Schedule::Week(vec![Weekday::Sat, Weekday::Sun])),
+        "month" | "monthly" => return Some(Schedule::Month(today.day())),
+        _ => {}
+    }
+
+    let lower = spec.to_lowercase();
+    if let Some(num) = ["st", "nd", "rd", "th"].iter().find_map(|suf| lower.strip_suffix(suf)) {
+        return num.parse::<u32>().ok().filter(|n| (1..=31).contains(n)).map(Schedule::Month);
+    }
+
+    let days: Option<Vec<Weekday>> = spec.split(',').filter(|s| !s.is_empty()).map(parse_weekday).collect();
+    days.filter(|d| !d.is_empty()).map(Schedule::Week)
+}
+
+// ─── materialization
+// the recurring tasks due on `day` that `bucket` doesn't have yet, and the ids
+// of every rule those tasks (or ones already there with the same title)
+// satisfy. only `today` and later days are filled in, each at most once per
+// rule. nothing is marked until the caller has the tasks and calls mark_applied.
+pub fn due(bucket: &DayBucket, day: &LogicalDay, today: &LogicalDay, limit: usize) -> (Vec<Task>, Vec<String>) {
+    let mut tasks: Vec<Task> = Vec::new();
+    let mut satisfied = Vec::new();
+    if day < today {
+        return (tasks, satisfied);
+    }
+    let Some(date) = parse_day(day) else { return (tasks, satisfied) };
+
+    let rules = RULES.lock().unwrap();
+    for rule in rules.iter() {
+        if rule.paused || day < &rule.starts || rule.applied.contains(day) || !rule.schedule.matches(date) {
+            continue;
+        }
+        // a rule's title can carry labels like any /todo
+        let words: Vec<&str> = rule.title.split_whitespace().collect();
+        let (words, labels) = labels::extract_labels(&words);
+        let title = words.join(" ");
+        let exists = bucket.todo.values().chain(bucket.done.values()).chain(tasks.iter()).any(|t| t.title == title);
+        if !exists {
+            if bucket.todo.len() + tasks.len() >= limit {
+                // try again once there's room
+                continue;
+            }
+            tasks.push(Task { recurrence_id: Some(rule.id.clone()), ..Task::new(title, day.clone()).with_labels(labels) });
+        }
+        satisfied.push(rule.id.clone());
+    }
+    (tasks, satisfied)
+}
+
+// records that the rules in `ids` have filled in `day`
+pub fn mark_applied(day: &LogicalDay, today: &LogicalDay, ids: &[String]) -> Result<(), String> {
+    let mut rules = RULES.lock().unwrap();
+    for rule in rules.iter_mut().filter(|r| ids.contains(&r.id)) {
+        if !rule.applied.contains(day) {
+            rule.applied.push(day.clone());
+        }
+    }
+    // days behind us will never be materialized again, so stop tracking them
+    for rule in rules.iter_mut() {
+        rule.applied.retain(|d| d >= today);
+    }
+    save_rules(&rules)
+}
+
+// ─── /todo every <schedule> <title>, or /every <schedule> <title>
+// "/todo every weekday Standup" adds a rule, but only when the word after
+// "every" is a schedule, so "/todo every morning stretch" stays a plain task
+pub fn is_rule_command(parts: &[&str]) -> bool {
+    let today = NaiveDate::from_ymd_opt(2000, 1, 1).unwrap_or_default();
+    parts.first() == Some(&"/todo") && parts.get(1) == Some(&"every")
+        && parts.get(2).and_then(|spec| parse_schedule(spec, today)).is_some()
+}
+
+// returns the new rule; the caller materializes it into today
+fn add_rule(parts: &[&str], today: &LogicalDay) -> Result<RecurrenceRule, String> {
+    let usage = "usage: /todo every <day|weekday|weekend|mon,thu|month|15th> <title>";
+    // "/todo every <spec> ..." reads like "/every <spec> ..."
+    let parts = if parts.first() == Some(&"/todo") { &parts[1..] } else { parts };
+    let spec = parts.get(1).ok_or(usage)?;
+    let title = parts.get(2..).map(|p| p.join(" ")).filter(|t| !t.is_empty()).ok_or(usage)?;
+    let today_date = parse_day(today).ok_or("bad logical day")?;
+    let schedule = parse_schedule(spec, today_date)
+        .ok_or_else(|| format!("couldn't understand the schedule '{}'. {}", spec, usage))?;
+
+    let mut rules = RULES.lock().unwrap();
+    if rules.iter().any(|r| r.title == title) {
+        return Err(format!("there's already a recurring task called '{}'", title));
+    }
+    let rule = RecurrenceRule {
+        id: Uuid::new_v4().to_string(),
+        title,
+        schedule,
+        paused: false,
+        starts: today.clone(),
+        applied: Vec::new(),
+    };
+    rules.push(rule.clone());
+    save_rules(&rules)?;
+    Ok(rule)
+}
+
+pub async fn command_every(parts: &[&str], _app: AppHandle) -> Result<String, String> {
+    let today = get_current_logical_day_key(None);
+    let rule = add_rule(parts, &today)?;
+    // the rule is saved either way; today's task can still show up on the next look
+    if let Err(e) = materialize_day(&today).await {
+        return Ok(format!("'{}' will repeat, but couldn't be added to today: {}", rule.title, e));
+    }
+    Ok(format!("'{}' will repeat. see /recurring", rule.title))
+}
+
+// 1-based position in the list or a unique id prefix
+fn find_rule(rules: &[RecurrenceRule], key: &str) -> Result<usize, String> {
+    if let Ok(n) = key.parse::<usize>() {
+        return if n >= 1 && n <= rules.len() { Ok(n - 1) } else { Err(format!("no recurring task #{}", n)) };
+    }
+    let hits: Vec<usize> = rules.iter().enumerate()
+        .filter(|(_, r)| r.id.starts_with(key) || r.title == key)
+        .map(|(i, _)| i)
+        .collect();
+    match hits.as_slice() {
+        [i] => Ok(*i),
+        [] => Err(format!("no recurring task matches '{}'", key)),
+        _ => Err(format!("'{}' matches more than one recurring task", key)),
+    }
+}
+
+fn list_rules(rules: &[RecurrenceRule]) -> String {
+    if rules.is_empty() {
+        return "no recurring tasks. add one with /todo every <schedule> <title>".into();
+    }
+    rules.iter().enumerate()
+        .map(|(i, r)| format!(
+            "{}. {} ({}){} [{}]",
+            i + 1, r.title, r.schedule.describe(),
+            if r.paused { " paused" } else { "" },
+            &r.id[..8.min(r.id.len())]
+        ))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// ─── /recurring [pause|resume|delete <n|id>]
+pub async fn command_recurring(parts: &[&str], _app: AppHandle) -> Result<String, String> {
+    let mut rules = RULES.lock().unwrap();
+    if let Some(e) = RULES_ERROR.lock().unwrap().clone() {
+        return Err(format!("recurrences.json couldn't be read, so no recurring tasks are running: {}", e));
+    }
+    let action = match parts.get(1) {
+        None | Some(&"list") => return Ok(list_rules(&rules)),
+        Some(a) => *a,
+    };
+    let key = parts.get(2..).map(|p| p.join(" ")).filter(|k| !k.is_empty())
+        .ok_or_else(|| format!("usage: /recurring {} <number|id>", action))?;
+    let idx = find_rule(&rules, &key)?;
+
+    let msg = match action {
+        "pause" => {
+            rules[idx].paused = true;
+            format!("paused '{}'", rules[idx].title)
+        }
+        "resume" => {
+            rules[idx].paused = false;
+            format!("resumed '{}'", rules[idx].title)
+        }
+        "delete" => {
+            let r = rules.remove(idx);
+            // tasks it already created stay where they are
+            format!("deleted recurring task '{}'", r.title)
+        }
+        other => return Err(format!("unknown /recurring action '{other}'. use list, pause, resume, or delete.")),
+    };
+    save_rules(&rules)?;
+    Ok(msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(s: &str) -> Vec<&str> { s.split_whitespace().collect() }
+
+    #[test]
+    fn todo_every_with_a_schedule_is_a_rule() {
+        assert!(is_rule_command(&words("/todo every weekday Standup")));
+        assert!(is_rule_command(&words("/todo every mon,thu Review PRs")));
+        assert!(is_rule_command(&words("/todo every 15th pay rent")));
+        // no title is still a rule, so it gets the usage message
+        assert!(is_rule_command(&words("/todo every day")));
+    }
+
+    #[test]
+    fn todo_every_without_a_schedule_is_a_task() {
+        assert!(!is_rule_command(&words("/todo every morning stretch")));
+        assert!(!is_rule_command(&words("/todo everything else")));
+        assert!(!is_rule_command(&words("/todo Standup")));
+        assert!(!is_rule_command(&words("/every weekday Standup")));
+    }
+
+    #[test]
+    fn schedules() {
+        let today = NaiveDate::from_ymd_opt(2025, 6, 4).unwrap();
+        assert_eq!(parse_schedule("weekday", today), Some(Schedule::Weekday));
+        assert_eq!(parse_schedule("mon,thu", today), Some(Schedule::Week(vec![Weekday::Mon, Weekday::Thu])));
+        assert_eq!(parse_schedule("15th", today), Some(Schedule::Month(15)));
+        assert_eq!(parse_schedule("month", today), Some(Schedule::Month(4)));
+        assert_eq!(parse_schedule("32nd", today), None);
+        assert_eq!(parse_schedule("morning", today), None);
+    }
+}
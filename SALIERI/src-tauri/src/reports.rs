@@ -0,0 +1,221 @@
+// productivity reports over a range of logical days, built from the task store
+// and the user stats
+use std::collections::BTreeMap;
+use chrono::{Datelike, Duration as ChronoDuration, Local, NaiveDate, Weekday};
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::dateparse;
+use crate::tasks::{format_duration, get_current_logical_day_key, logical_day_of, snapshot_store, Store};
+use crate::user::User;
+
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct DayStat {
+    pub day: String,
+    pub created: usize,
+    pub completed: usize,
+    pub focused_secs: u64,
+    pub pomodoros: u64,
+    // finished tasks that had an estimate: what was expected and what it took
+    pub estimated: usize,
+    pub within_estimate: usize,
+    pub estimated_secs: u64,
+    pub actual_secs: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Report {
+    pub from: String,
+    pub to: String,
+    pub created: usize,
+    pub completed: usize,
+    // share of the tasks on these days that got done
+    pub completion_rate: f64,
+    pub focused_secs: u64,
+    pub avg_focused_secs_per_day: u64,
+    pub pomodoros: u64,
+    // by tasks completed; None when nothing was
+    pub busiest_weekday: Option<String>,
+    // most consecutive days with at least one task done
+    pub longest_streak: u32,
+    pub estimated: usize,
+    // of those, how many took no longer than estimated
+    pub within_estimate: usize,
+    // time taken over time estimated; 1.5 means half as long again
+    pub estimate_ratio: Option<f64>,
+    // one entry per day in the range, for charts
+    pub days: Vec<DayStat>,
+}
+
+fn key(d: NaiveDate) -> String { d.format("%Y-%m-%d").to_string() }
+
+pub fn build_report(st: &Store, user: &User, from: NaiveDate, to: NaiveDate) -> Report {
+    let mut days: BTreeMap<String, DayStat> = BTreeMap::new();
+    let mut d = from;
+    while d <= to {
+        days.insert(key(d), DayStat { day: key(d), ..Default::default() });
+        d += ChronoDuration::days(1);
+    }
+
+    let now = Local::now();
+    let mut open = 0;
+    for (day, bucket) in st {
+        let in_range = days.contains_key(day);
+        if let Some(s) = days.get_mut(day) {
+            open += bucket.todo.len();
+            s.completed += bucket.done.len();
+            for t in bucket.done.values() {
+                if let Some(e) = t.estimate {
+                    s.estimated += 1;
+                    s.estimated_secs += e;
+                    s.actual_secs += t.time_spent;
+                    s.within_estimate += (t.time_spent <= e) as usize;
+                }
+            }
+        }
+        for t in bucket.todo.values().chain(bucket.done.values()) {
+            if let Some(s) = days.get_mut(&t.created_at) {
+                s.created += 1;
+            }
+            // sessions say when the work happened; older time only knows its day
+            for session in &t.sessions {
+                if let Some(s) = days.get_mut(&logical_day_of(session.start)) {
+                    s.focused_secs += session.seconds(now);
+                }
+            }
+            if in_range {
+                days.get_mut(day).unwrap().focused_secs += t.untimed.unwrap_or(0);
+            }
+        }
+    }
+    for (day, n) in &user.pomodoros_by_day {
+        if let Some(s) = days.get_mut(day) {
+            s.pomodoros += n;
+        }
+    }
+
+    let stats: Vec<DayStat> = days.into_values().collect();
+    let completed: usize = stats.iter().map(|s| s.completed).sum();
+    let focused_secs: u64 = stats.iter().map(|s| s.focused_secs).sum();
+    let estimated_secs: u64 = stats.iter().map(|s| s.estimated_secs).sum();
+
+    // completions per weekday, monday first
+    let mut by_weekday = [0usize; 7];
+    let mut longest_streak = 0;
+    let mut streak = 0;
+    for s in &stats {
+        if let Ok(date) = NaiveDate::parse_from_str(&s.day, "%Y-%m-%d") {
+            by_weekday[date.weekday().num_days_from_monday() as usize] += s.completed;
+        }
+        streak = if s.completed > 0 { streak + 1 } else { 0 };
+        longest_streak = longest_streak.max(streak);
+    }
+    // ties go to the earlier weekday
+    let busiest_weekday = (0..7)
+        .filter(|&i| by_weekday[i] > 0)
+        .fold(None, |best: Option<usize>, i| match best {
+            Some(b) if by_weekday[b] >= by_weekday[i] => best,
+            _ => Some(i),
+        })
+        .map(|i| Weekday::try_from(i as u8).map(|wd| wd.to_string()).unwrap_or_default());
+
+    Report {
+        from: key(from),
+        to: key(to),
+        created: stats.iter().map(|s| s.created).sum(),
+        completed,
+        completion_rate: if completed + open == 0 { 0.0 } else { completed as f64 / (completed + open) as f64 },
+        focused_secs,
+        avg_focused_secs_per_day: focused_secs / stats.len().max(1) as u64,
+        pomodoros: stats.iter().map(|s| s.pomodoros).sum(),
+        busiest_weekday,
+        longest_streak,
+        estimated: stats.iter().map(|s| s.estimated).sum(),
+        within_estimate: stats.iter().map(|s| s.within_estimate).sum(),
+        estimate_ratio: (estimated_secs > 0).then(|| stats.iter().map(|s| s.actual_secs).sum::<u64>() as f64 / estimated_secs as f64),
+        days: stats,
+    }
+}
+
+// "week" and "month" are the current ones; anything else goes to parse_range
+fn resolve_range(words: &[&str]) -> Result<(NaiveDate, NaiveDate), String> {
+    let today = NaiveDate::parse_from_str(&get_current_logical_day_key(None), "%Y-%m-%d").map_err(|e| e.to_string())?;
+    let words: Vec<&str> = match words {
+        [] | ["week"] => vec!["this", "week"],
+        ["month"] => vec!["this", "month"],
+        other => other.to_vec(),
+    };
+    dateparse::parse_range(&words, today).ok_or_else(|| format!(
+        "couldn't understand '{}'. try week, month, last week or 2025-06-01..2025-06-30",
+        words.join(" ")
+    ))
+}
+
+async fn report_for(words: &[&str], app: &AppHandle) -> Result<Report, String> {
+    let (from, to) = resolve_range(words)?;
+    let user = User::load_user(app)?;
+    Ok(build_report(&snapshot_store().await, &user, from, to))
+}
+
+#[tauri::command]
+pub async fn get_report(app: AppHandle, range: Option<String>) -> Result<Report, String> {
+    let range = range.unwrap_or_default();
+    report_for(&range.split_whitespace().collect::<Vec<_>>(), &app).await
+}
+
+// ─── /report [week|month|<range>]
+pub async fn command_report(parts: &[&str], app: AppHandle) -> Result<String, String> {
+    if parts.get(1) == Some(&"estimates") {
+        return command_report_estimates(&parts[2..], &app).await;
+    }
+    let r = report_for(parts.get(1..).unwrap_or(&[]), &app).await?;
+    let mut out = format!(
+        "{} to {}\ncreated: {}  completed: {}  ({:.0}% of tasks on these days)\nfocused: {} (avg {} a day)\npomodoros: {}\nbusiest day: {}\nlongest streak: {} day(s)",
+        r.from, r.to,
+        r.created, r.completed, r.completion_rate * 100.0,
+        format_duration(r.focused_secs), format_duration(r.avg_focused_secs_per_day),
+        r.pomodoros,
+        r.busiest_weekday.as_deref().unwrap_or("none yet"),
+        r.longest_streak,
+    );
+    if let Some(ratio) = r.estimate_ratio {
+        out.push_str(&format!("\nestimates: {} of {} done in time, {:.1}x the estimate overall", r.within_estimate, r.estimated, ratio));
+    }
+    Ok(out)
+}
+
+// ─── /report estimates [range]
+// estimate accuracy week by week, so you can see whether it's improving.
+// defaults to the last 8 weeks
+async fn command_report_estimates(words: &[&str], app: &AppHandle) -> Result<String, String> {
+    let words = if words.is_empty() { &["last", "8", "weeks"][..] } else { words };
+    let r = report_for(words, app).await?;
+    if r.estimated == 0 {
+        return Ok(format!("no finished tasks with an estimate between {} and {}. add one with /todo <task> ~45m", r.from, r.to));
+    }
+
+    // (week start, tasks, in time, estimated, actual)
+    let mut weeks: Vec<(NaiveDate, usize, usize, u64, u64)> = Vec::new();
+    for s in r.days.iter().filter(|s| s.estimated > 0) {
+        let Ok(date) = NaiveDate::parse_from_str(&s.day, "%Y-%m-%d") else { continue };
+        let monday = date - ChronoDuration::days(date.weekday().num_days_from_monday() as i64);
+        match weeks.last_mut() {
+            Some(w) if w.0 == monday => {
+                w.1 += s.estimated;
+                w.2 += s.within_estimate;
+                w.3 += s.estimated_secs;
+                w.4 += s.actual_secs;
+            }
+            _ => weeks.push((monday, s.estimated, s.within_estimate, s.estimated_secs, s.actual_secs)),
+        }
+    }
+
+    let mut out: Vec<String> = weeks.iter()
+        .map(|(monday, n, in_time, est, actual)| format!(
+            "week of {}: {} of {} in time, {:.1}x ({} estimated, {} taken)",
+            key(*monday), in_time, n, *actual as f64 / *est as f64, format_duration(*est), format_duration(*actual)
+        ))
+        .collect();
+    out.push(format!("overall: {} of {} in time, {:.1}x", r.within_estimate, r.estimated, r.estimate_ratio.unwrap_or(0.0)));
+    Ok(out.join("\n"))
+}
@@ -0,0 +1,113 @@
+// work sessions: each /doing -> /break or /done span on a task, kept as a
+// start/end pair so we know when the work happened, not just how much
+use std::collections::BTreeMap;
+use chrono::{DateTime, Duration as ChronoDuration, Local, NaiveDate, Timelike};
+use serde::{Serialize, Deserialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    pub start: DateTime<Local>,
+    // None while the task is still being worked on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<DateTime<Local>>,
+}
+
+impl Session {
+    pub fn open(now: DateTime<Local>) -> Session {
+        Session { start: now, end: None }
+    }
+
+    pub fn seconds(&self, now: DateTime<Local>) -> u64 {
+        (self.end.unwrap_or(now) - self.start).num_seconds().max(0) as u64
+    }
+}
+
+pub fn total(sessions: &[Session], now: DateTime<Local>) -> u64 {
+    sessions.iter().map(|s| s.seconds(now)).sum()
+}
+
+// seconds worked in each wall-clock hour, keyed by (date, hour). a session
+// running over the top of an hour is split between the two.
+pub fn by_hour(sessions: &[Session], now: DateTime<Local>) -> BTreeMap<(NaiveDate, u32), u64> {
+    let mut out = BTreeMap::new();
+    for s in sessions {
+        let end = s.end.unwrap_or(now);
+        let mut at = s.start;
+        while at < end {
+            let hour_start = at.with_minute(0).and_then(|t| t.with_second(0)).and_then(|t| t.with_nanosecond(0)).unwrap_or(at);
+            let next = (hour_start + ChronoDuration::hours(1)).min(end);
+            *out.entry((at.date_naive(), at.hour())).or_insert(0) += (next - at).num_seconds().max(0) as u64;
+            at = next;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use crate::tasks::Task;
+
+    fn at(day: u32, h: u32, m: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2025, 6, day, h, m, 0).unwrap()
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 6, day).unwrap()
+    }
+
+    #[test]
+    fn open_session_counts_up_to_now_until_closed() {
+        let mut s = Session::open(at(4, 9, 0));
+        assert_eq!(s.seconds(at(4, 9, 10)), 600);
+        s.end = Some(at(4, 9, 20));
+        assert_eq!(s.seconds(at(4, 12, 0)), 1200);
+    }
+
+    #[test]
+    fn starting_twice_keeps_one_session_and_stopping_closes_it() {
+        let mut task = Task::new("write report".into(), "2025-06-04".into());
+        task.start_session(at(4, 9, 0));
+        task.start_session(at(4, 9, 5));
+        assert_eq!(task.sessions, vec![Session::open(at(4, 9, 0))]);
+        task.stop_session(at(4, 9, 30));
+        assert_eq!(task.sessions[0].end, Some(at(4, 9, 30)));
+        // stopping again doesn't move the end
+        task.stop_session(at(4, 10, 0));
+        assert_eq!(task.sessions[0].end, Some(at(4, 9, 30)));
+    }
+
+    #[test]
+    fn time_spent_is_the_sessions_plus_untimed() {
+        let mut task = Task::new("write report".into(), "2025-06-04".into());
+        task.untimed = Some(300);
+        task.start_session(at(4, 9, 0));
+        task.stop_session(at(4, 9, 30));
+        task.start_session(at(4, 14, 0));
+        task.stop_session(at(4, 14, 15));
+        assert_eq!(task.time_spent, 300 + 1800 + 900);
+        // an open session counts up to now
+        task.start_session(at(4, 16, 0));
+        task.refresh_time(at(4, 16, 10));
+        assert_eq!(task.time_spent, 300 + 1800 + 900 + 600);
+    }
+
+    #[test]
+    fn a_session_over_midnight_is_split_between_the_days() {
+        let sessions = vec![Session { start: at(4, 23, 30), end: Some(at(5, 0, 45)) }];
+        let hours = by_hour(&sessions, at(5, 9, 0));
+        assert_eq!(hours.get(&(date(4), 23)), Some(&1800));
+        assert_eq!(hours.get(&(date(5), 0)), Some(&2700));
+        assert_eq!(hours.len(), 2);
+        assert_eq!(hours.values().sum::<u64>(), total(&sessions, at(5, 9, 0)));
+    }
+
+    #[test]
+    fn open_session_is_split_up_to_now() {
+        let sessions = vec![Session::open(at(4, 10, 50))];
+        let hours = by_hour(&sessions, at(4, 11, 5));
+        assert_eq!(hours.get(&(date(4), 10)), Some(&600));
+        assert_eq!(hours.get(&(date(4), 11)), Some(&300));
+    }
+}
@@ -0,0 +1,588 @@
+use std::{fs, path::{Path, PathBuf}, sync::{Arc, Mutex, RwLock}};
+use directories::ProjectDirs;
+use lazy_static::lazy_static;
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use serde::{Serialize, Deserialize, de::DeserializeOwned};
+use serde_json::json;
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+use crate::tasks::{Store, Task, persist_global_store, snapshot_store};
+use crate::theme::SETTINGS_STORE_FILENAME;
+use crate::fileaccess::write_atomic;
+
+pub const STORAGE_KEY: &str = "storage_backend";
+
+// ─── backend trait
+pub trait TaskStorage: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn location(&self) -> PathBuf;
+    fn load(&self) -> Result<Store, String>;
+    // true when `save` can be handed only the days that changed
+    fn incremental(&self) -> bool;
+    // `st` is the whole store, or just the touched days when `incremental()`.
+    // an empty bucket means the day no longer has any tasks.
+    fn save(&self, st: &Store) -> Result<(), String>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageKind {
+    Json,
+    Sqlite,
+}
+
+impl StorageKind {
+    pub fn parse(s: &str) -> Option<StorageKind> {
+        match s {
+            "json" => Some(StorageKind::Json),
+            "sqlite" => Some(StorageKind::Sqlite),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StorageKind::Json => "json",
+            StorageKind::Sqlite => "sqlite",
+        }
+    }
+}
+
+lazy_static! {
+    static ref BACKEND: RwLock<Arc<dyn TaskStorage>> = RwLock::new(Arc::new(JsonStorage));
+    // set when the store on disk couldn't be read; nothing gets written over it while set
+    static ref LOAD_ERROR: RwLock<Option<String>> = RwLock::new(None);
+}
+
+pub fn set_load_error(e: Option<String>) {
+    *LOAD_ERROR.write().unwrap() = e;
+}
+
+pub fn load_error() -> Option<String> {
+    LOAD_ERROR.read().unwrap().clone()
+}
+
+pub fn ensure_writable() -> Result<(), String> {
+    match load_error() {
+        Some(e) => Err(format!("task store is read-only until this is fixed: {}", e)),
+        None => Ok(()),
+    }
+}
+
+#[tauri::command]
+pub fn get_store_error() -> Option<String> {
+    load_error()
+}
+
+pub fn backend() -> Arc<dyn TaskStorage> {
+    BACKEND.read().unwrap().clone()
+}
+
+pub fn open_backend(kind: StorageKind) -> Result<Arc<dyn TaskStorage>, String> {
+    Ok(match kind {
+        StorageKind::Json => Arc::new(JsonStorage),
+        StorageKind::Sqlite => Arc::new(SqliteStorage::open(&sqlite_path())?),
+    })
+}
+
+// has to run before anything touches the task store during startup
+pub fn select_backend(kind: StorageKind) -> Result<(), String> {
+    let b = open_backend(kind)?;
+    *BACKEND.write().unwrap() = b;
+    Ok(())
+}
+
+// ─── paths
+pub fn data_dir() -> PathBuf {
+    ProjectDirs::from("com", "salieri", "salieri")
+        .map(|d| d.data_local_dir().to_path_buf())
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+pub fn store_path() -> PathBuf { data_dir().join("tasks_store.json") }
+
+pub fn sqlite_path() -> PathBuf { data_dir().join("tasks.sqlite3") }
+
+fn ensure_data_dir() {
+    let dir = data_dir();
+    if !dir.exists() { let _ = fs::create_dir_all(&dir); }
+}
+
+// "missing" when there's no file. one that can't be parsed is copied aside
+// first and the error says where, so the caller can refuse to save over it
+pub fn load_json_or_quarantine<T: DeserializeOwned>(p: &Path) -> Result<T, String> {
+    if !p.exists() { return Err("missing".into()); }
+    let text = fs::read_to_string(p).map_err(|e| e.to_string())?;
+    serde_json::from_str(&text).map_err(|e| quarantine(p, &e.to_string()))
+}
+
+pub fn save_json<T: Serialize>(p: &Path, d: &T) -> Result<(), String> {
+    ensure_data_dir();
+    write_atomic(p, serde_json::to_string_pretty(d).map_err(|e| e.to_string())?.as_bytes())
+}
+
+// copy an unreadable file aside as `<name>.<timestamp>.corrupt` and explain what happened
+fn quarantine(p: &Path, why: &str) -> String {
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let name = p.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    let kept = p.with_file_name(format!("{}.{}.corrupt", name, stamp));
+    match fs::copy(p, &kept) {
+        Ok(_) => format!("{} is corrupt ({}); a copy was kept at {}", p.display(), why, kept.display()),
+        Err(e) => format!("{} is corrupt ({}) and couldn't be copied aside: {}", p.display(), why, e),
+    }
+}
+
+// ─── on-disk schema
+// v1: bare `{ day: bucket }` map with free-form status strings
+// v2: `{ schema_version, days }` envelope, status is a TaskStatus
+pub const STORE_SCHEMA_VERSION: u32 = 2;
+
+#[derive(Serialize, Deserialize)]
+struct StoreFile<S> {
+    schema_version: u32,
+    days: S,
+}
+
+type Migration = fn(serde_json::Value) -> Result<serde_json::Value, String>;
+
+// MIGRATIONS[n] upgrades a store from version n + 1 to n + 2
+const MIGRATIONS: &[Migration] = &[
+    migrate_v1_to_v2,
+];
+
+fn migrate_v1_to_v2(v: serde_json::Value) -> Result<serde_json::Value, String> {
+    let serde_json::Value::Object(mut days) = v else {
+        return Err("v1 store is not a map of days".into());
+    };
+    for bucket in days.values_mut() {
+        for list in ["todo", "done"] {
+            let Some(tasks) = bucket.get_mut(list).and_then(|l| l.as_object_mut()) else { continue };
+            for task in tasks.values_mut() {
+                let old = task.get("status").and_then(|s| s.as_str()).unwrap_or("").to_lowercase();
+                // anything unrecognised falls back to whichever list it was filed under
+                let status = match old.as_str() {
+                    "todo" | "doing" | "done" => old,
+                    _ => list.to_string(),
+                };
+                task["status"] = serde_json::Value::String(status);
+            }
+        }
+    }
+    Ok(serde_json::json!({ "schema_version": 2, "days": days }))
+}
+
+fn schema_version_of(v: &serde_json::Value) -> u32 {
+    v.get("schema_version")
+        .and_then(|n| n.as_u64())
+        .map(|n| n as u32)
+        .unwrap_or(1)
+}
+
+// returns the upgraded value and the version it started at
+fn migrate_store_value(mut v: serde_json::Value) -> Result<(serde_json::Value, u32), String> {
+    let from = schema_version_of(&v);
+    if from == 0 || from > STORE_SCHEMA_VERSION {
+        return Err(format!(
+            "tasks_store.json has schema version {} but this build only understands up to {}",
+            from, STORE_SCHEMA_VERSION
+        ));
+    }
+    for migrate in &MIGRATIONS[(from as usize - 1)..] {
+        v = migrate(v)?;
+    }
+    Ok((v, from))
+}
+
+fn write_store_file(path: &Path, st: &Store) -> Result<(), String> {
+    let file = StoreFile { schema_version: STORE_SCHEMA_VERSION, days: st };
+    write_atomic(path, serde_json::to_string_pretty(&file).map_err(|e| e.to_string())?.as_bytes())
+}
+
+// reads a store file, upgrading it in place when it's from an older schema.
+// a file this build can't read is left exactly as it is.
+fn read_store_file(path: &Path) -> Result<Store, String> {
+    if !path.exists() { return Err("missing".into()); }
+    let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let raw: serde_json::Value = serde_json::from_str(&text)
+        .map_err(|e| quarantine(path, &e.to_string()))?;
+    let (migrated, from) = migrate_store_value(raw)?;
+    let file: StoreFile<Store> = serde_json::from_value(migrated)
+        .map_err(|e| quarantine(path, &e.to_string()))?;
+
+    if from < STORE_SCHEMA_VERSION {
+        // keep the original around before upgrading it in place
+        let backup = path.with_extension(format!("v{}.json.bak", from));
+        fs::copy(path, &backup).map_err(|e| format!("failed to back up store before migration: {}", e))?;
+        write_store_file(path, &file.days)?;
+        println!("migrated {} from v{} to v{} (backup at {})", path.display(), from, STORE_SCHEMA_VERSION, backup.display());
+    }
+    Ok(file.days)
+}
+
+// ─── json backend
+pub struct JsonStorage;
+
+impl JsonStorage {
+    fn read(&self) -> Result<Store, String> {
+        read_store_file(&store_path())
+    }
+}
+
+impl TaskStorage for JsonStorage {
+    fn name(&self) -> &'static str { "json" }
+
+    fn location(&self) -> PathBuf { store_path() }
+
+    fn load(&self) -> Result<Store, String> {
+        match self.read() {
+            Err(e) if e == "missing" => {
+                println!("tasks_store.json not found, creating a new one.");
+                let empty_store = Store::new();
+                // best-effort for first run
+                let _ = self.save(&empty_store);
+                Ok(empty_store)
+            }
+            other => other,
+        }
+    }
+
+    fn incremental(&self) -> bool { false }
+
+    fn save(&self, st: &Store) -> Result<(), String> {
+        ensure_data_dir();
+        write_store_file(&store_path(), st)
+    }
+}
+
+// ─── sqlite backend
+// one row per task; the task itself is kept as json so new fields on Task
+// don't need a table migration
+pub struct SqliteStorage {
+    conn: Mutex<Connection>,
+    path: PathBuf,
+}
+
+impl SqliteStorage {
+    pub fn open(path: &Path) -> Result<SqliteStorage, String> {
+        ensure_data_dir();
+        let conn = Connection::open(path).map_err(|e| e.to_string())?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS meta (
+                key   TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS tasks (
+                day      TEXT NOT NULL,
+                list     TEXT NOT NULL,
+                position INTEGER NOT NULL,
+                id       TEXT NOT NULL,
+                title    TEXT NOT NULL,
+                status   TEXT NOT NULL,
+                data     TEXT NOT NULL,
+                PRIMARY KEY (day, list, id)
+            );
+            CREATE INDEX IF NOT EXISTS tasks_by_day ON tasks(day);",
+        ).map_err(|e| e.to_string())?;
+        // a database from a newer build is left exactly as it is, like tasks_store.json
+        let version: Option<String> = conn
+            .query_row("SELECT value FROM meta WHERE key = 'schema_version'", [], |r| r.get(0))
+            .optional()
+            .map_err(|e| e.to_string())?;
+        match version {
+            None => {
+                conn.execute(
+                    "INSERT INTO meta (key, value) VALUES ('schema_version', ?1)",
+                    params![STORE_SCHEMA_VERSION.to_string()],
+                ).map_err(|e| e.to_string())?;
+            }
+            Some(v) if v.parse::<u32>().is_ok_and(|n| n <= STORE_SCHEMA_VERSION) => {}
+            Some(v) => return Err(format!(
+                "{} has schema version {} but this build only understands up to {}",
+                path.display(), v, STORE_SCHEMA_VERSION
+            )),
+        }
+        Ok(SqliteStorage { conn: Mutex::new(conn), path: path.to_path_buf() })
+    }
+
+    // full copy, dropping any days that aren't in `st`. all or nothing, so a
+    // failed copy leaves the old tasks in place
+    pub fn replace_all(&self, st: &Store) -> Result<(), String> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction().map_err(|e| e.to_string())?;
+        tx.execute("DELETE FROM tasks", []).map_err(|e| e.to_string())?;
+        write_days(&tx, st)?;
+        tx.commit().map_err(|e| e.to_string())
+    }
+
+    pub fn task_count(&self) -> Result<u64, String> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row("SELECT COUNT(*) FROM tasks", [], |r| r.get::<_, i64>(0))
+            .map(|n| n as u64)
+            .map_err(|e| e.to_string())
+    }
+}
+
+impl TaskStorage for SqliteStorage {
+    fn name(&self) -> &'static str { "sqlite" }
+
+    fn location(&self) -> PathBuf { self.path.clone() }
+
+    fn load(&self) -> Result<Store, String> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare("SELECT day, list, data FROM tasks ORDER BY day, list, position")
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([], |r| Ok((r.get::<_, String>(0)?, r.get::<_, String>(1)?, r.get::<_, String>(2)?)))
+            .map_err(|e| e.to_string())?;
+
+        let mut st = Store::new();
+        for row in rows {
+            let (day, list, data) = row.map_err(|e| e.to_string())?;
+            let task: Task = serde_json::from_str(&data).map_err(|e| format!("bad task row on {}: {}", day, e))?;
+            let bucket = st.entry(day).or_default();
+            let target = if list == "done" { &mut bucket.done } else { &mut bucket.todo };
+            target.insert(task.id.clone(), task);
+        }
+        Ok(st)
+    }
+
+    fn incremental(&self) -> bool { true }
+
+    fn save(&self, st: &Store) -> Result<(), String> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction().map_err(|e| e.to_string())?;
+        write_days(&tx, st)?;
+        tx.commit().map_err(|e| e.to_string())
+    }
+}
+
+// rewrites each day in `st` inside the caller's transaction
+fn write_days(tx: &Transaction, st: &Store) -> Result<(), String> {
+    for (day, bucket) in st {
+        tx.execute("DELETE FROM tasks WHERE day = ?1", params![day]).map_err(|e| e.to_string())?;
+        for (list, tasks) in [("todo", &bucket.todo), ("done", &bucket.done)] {
+            for (pos, task) in tasks.values().enumerate() {
+                let data = serde_json::to_string(task).map_err(|e| e.to_string())?;
+                tx.execute(
+                    "INSERT INTO tasks (day, list, position, id, title, status, data)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                    params![day, list, pos as i64, task.id, task.title, task.status.as_str(), data],
+                ).map_err(|e| e.to_string())?;
+            }
+        }
+    }
+    Ok(())
+}
+
+// ─── importer
+// one-shot copy of tasks_store.json into the sqlite database.
+// refuses to run over a database that already holds tasks.
+pub fn import_json_into_sqlite() -> Result<(usize, usize), String> {
+    let db = SqliteStorage::open(&sqlite_path())?;
+    if db.task_count()? > 0 {
+        return Err(format!("{} already has tasks; not importing over them", sqlite_path().display()));
+    }
+    let st = match JsonStorage.read() {
+        Err(e) if e == "missing" => Store::new(),
+        other => other?,
+    };
+    db.replace_all(&st)?;
+
+    let tasks = st.values().map(|b| b.todo.len() + b.done.len()).sum();
+    Ok((st.len(), tasks))
+}
+
+// ─── /storage
+pub async fn command_storage(parts: &[&str], app: AppHandle) -> Result<String, String> {
+    let current = backend();
+    match parts.get(1) {
+        None => Ok(format!("tasks are stored in {} ({})", current.name(), current.location().display())),
+
+        Some(&"import") => {
+            // make sure tasks_store.json has everything before copying it
+            if current.name() == "json" {
+                persist_global_store().await?;
+            }
+            let (days, tasks) = tauri::async_runtime::spawn_blocking(import_json_into_sqlite)
+                .await
+                .map_err(|e| format!("Failed to join import task: {}", e))??;
+            Ok(format!("imported {} tasks across {} days into {}", tasks, days, sqlite_path().display()))
+        }
+
+        Some(arg) => {
+            ensure_writable()?;
+            let kind = StorageKind::parse(arg)
+                .ok_or_else(|| format!("unknown /storage argument '{arg}'. use json, sqlite, or import."))?;
+            if kind.as_str() == current.name() {
+                return Ok(format!("already using {}", kind.as_str()));
+            }
+
+            // carry everything we have in memory over to the new backend
+            let st = snapshot_store().await;
+            let next = tauri::async_runtime::spawn_blocking(move || -> Result<Arc<dyn TaskStorage>, String> {
+                match kind {
+                    StorageKind::Json => JsonStorage.save(&st)?,
+                    StorageKind::Sqlite => SqliteStorage::open(&sqlite_path())?.replace_all(&st)?,
+                }
+                open_backend(kind)
+            })
+            .await
+            .map_err(|e| format!("Failed to join switch task: {}", e))??;
+            *BACKEND.write().unwrap() = next;
+
+            let settings = app.store(SETTINGS_STORE_FILENAME).map_err(|e| e.to_string())?;
+            settings.set(STORAGE_KEY, json!(kind.as_str()));
+            settings.save().map_err(|e| e.to_string())?;
+
+            Ok(format!("tasks now stored in {} ({})", kind.as_str(), backend().location().display()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tasks::TaskStatus;
+
+    // a fresh directory under the system temp dir for one test
+    fn scratch() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("salieri-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    const V1: &str = r#"{
+        "2025-06-01": {
+            "todo": {
+                "a": { "id": "a", "title": "write report", "status": "Doing", "created_at": "2025-06-01", "time_spent": 30 },
+                "b": { "id": "b", "title": "call bob", "status": "whatever", "created_at": "2025-06-01", "time_spent": 0 }
+            },
+            "done": {
+                "c": { "id": "c", "title": "standup", "status": "", "created_at": "2025-06-01", "time_spent": 900 }
+            }
+        }
+    }"#;
+
+    #[test]
+    fn v1_store_is_upgraded_in_place() {
+        let dir = scratch();
+        let path = dir.join("tasks_store.json");
+        fs::write(&path, V1).unwrap();
+
+        let st = read_store_file(&path).unwrap();
+        let day = &st["2025-06-01"];
+        assert_eq!(day.todo["a"].status, TaskStatus::Doing);
+        // unknown statuses fall back to the list the task was filed under
+        assert_eq!(day.todo["b"].status, TaskStatus::Todo);
+        assert_eq!(day.done["c"].status, TaskStatus::Done);
+        assert_eq!(day.done["c"].time_spent, 900);
+
+        let written: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(written["schema_version"], STORE_SCHEMA_VERSION);
+        assert_eq!(written["days"]["2025-06-01"]["todo"]["b"]["status"], "todo");
+
+        // reading it again is a plain v2 load
+        assert_eq!(read_store_file(&path).unwrap()["2025-06-01"].todo.len(), 2);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn migration_keeps_the_original_file() {
+        let dir = scratch();
+        let path = dir.join("tasks_store.json");
+        fs::write(&path, V1).unwrap();
+
+        read_store_file(&path).unwrap();
+        assert_eq!(fs::read_to_string(dir.join("tasks_store.v1.json.bak")).unwrap(), V1);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn current_store_is_not_backed_up() {
+        let dir = scratch();
+        let path = dir.join("tasks_store.json");
+        fs::write(&path, r#"{ "schema_version": 2, "days": {} }"#).unwrap();
+
+        assert!(read_store_file(&path).unwrap().is_empty());
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn newer_store_is_refused_and_left_alone() {
+        let dir = scratch();
+        let path = dir.join("tasks_store.json");
+        let newer = r#"{ "schema_version": 99, "days": {} }"#;
+        fs::write(&path, newer).unwrap();
+
+        let err = read_store_file(&path).unwrap_err();
+        assert!(err.contains("schema version 99"), "{}", err);
+        assert_eq!(fs::read_to_string(&path).unwrap(), newer);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn unreadable_json_is_copied_aside_and_left_alone() {
+        let dir = scratch();
+        let path = dir.join("recurrences.json");
+        fs::write(&path, "[{ not json").unwrap();
+
+        let err = load_json_or_quarantine::<Vec<String>>(&path).unwrap_err();
+        assert!(err.contains("is corrupt"), "{}", err);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "[{ not json");
+        let kept: Vec<_> = fs::read_dir(&dir).unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name().to_string_lossy().ends_with(".corrupt"))
+            .collect();
+        assert_eq!(kept.len(), 1);
+        assert_eq!(fs::read_to_string(kept[0].path()).unwrap(), "[{ not json");
+
+        assert_eq!(load_json_or_quarantine::<Vec<String>>(&dir.join("nope.json")).unwrap_err(), "missing");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    fn one_task_store(day: &str, title: &str) -> Store {
+        let raw = format!(r#"{{"{}": {{"todo": {{"a": {{"id": "a", "title": "{}", "status": "todo", "created_at": "{}", "time_spent": 0}}}}, "done": {{}}}}}}"#, day, title, day);
+        serde_json::from_str(&raw).unwrap()
+    }
+
+    #[test]
+    fn failed_replace_all_keeps_the_old_tasks() {
+        let dir = scratch();
+        let db = SqliteStorage::open(&dir.join("tasks.sqlite3")).unwrap();
+        db.replace_all(&one_task_store("2025-06-01", "write report")).unwrap();
+
+        // two rows with the same id on one list break the primary key part way through
+        let mut bad = one_task_store("2025-06-02", "call bob");
+        let dup = bad["2025-06-02"].todo["a"].clone();
+        bad["2025-06-02"].todo.insert("b".into(), dup);
+        assert!(db.replace_all(&bad).is_err());
+
+        let st = db.load().unwrap();
+        assert_eq!(st.len(), 1);
+        assert_eq!(st["2025-06-01"].todo["a"].title, "write report");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn newer_sqlite_database_is_refused_and_left_alone() {
+        let dir = scratch();
+        let path = dir.join("tasks.sqlite3");
+        SqliteStorage::open(&path).unwrap();
+        // reopening one this build wrote is fine
+        SqliteStorage::open(&path).unwrap();
+
+        let next = (STORE_SCHEMA_VERSION + 1).to_string();
+        Connection::open(&path).unwrap()
+            .execute("UPDATE meta SET value = ?1 WHERE key = 'schema_version'", params![next]).unwrap();
+        let err = SqliteStorage::open(&path).err().unwrap();
+        assert!(err.contains(&format!("schema version {}", next)), "{}", err);
+
+        let kept: String = Connection::open(&path).unwrap()
+            .query_row("SELECT value FROM meta WHERE key = 'schema_version'", [], |r| r.get(0)).unwrap();
+        assert_eq!(kept, next);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
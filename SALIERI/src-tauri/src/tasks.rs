@@ -1,28 +1,52 @@
-use chrono::{Local, Duration as ChronoDuration, NaiveDate};
-use tauri::AppHandle;
+use chrono::{DateTime, Local, Duration as ChronoDuration, NaiveDate};
+use tauri::{AppHandle, Emitter};
 use uuid::Uuid;
 use once_cell::sync::Lazy;
-use std::{collections::HashMap, fs, path::{Path, PathBuf}, sync::Mutex, time::Duration};
-use directories::ProjectDirs;
+use std::{collections::HashMap, sync::Mutex, time::Duration};
 use tokio::sync::RwLock as TokioRwLock;
 use futures::executor;         
-use serde::{Serialize, Deserialize, de::DeserializeOwned};
+use serde::{Serialize, Deserialize};
 use tokio::sync::Mutex as TokioMutex;
 use lazy_static::lazy_static;
 use indexmap::IndexMap;
 
-use crate::user::increment_tasks_done;
+use crate::user::{increment_tasks_done, decrement_tasks_done};
+use crate::storage;
+use crate::config::config;
+use crate::recurrence;
+use crate::fuzzy;
+use crate::dateparse;
+use crate::labels::{self, Labels};
+use crate::journal::{self, Change};
+use crate::sessions::{self, Session};
+use crate::undo;
+
+// the backend's snapshot with the journal played over it
+fn load_with_journal(backend: &dyn storage::TaskStorage) -> Result<Store, String> {
+    let mut store = backend.load()?; // reads from disk and migrates older schemas
+    journal::replay(&mut store)?;
+    for bucket in store.values_mut() {
+        for t in bucket.todo.values_mut().chain(bucket.done.values_mut()) {
+            if t.untimed.is_none() {
+                // no sessions yet, so everything tracked so far predates them
+                t.untimed = Some(t.time_spent.saturating_sub(sessions::total(&t.sessions, Local::now())));
+            }
+        }
+    }
+    Ok(store)
+}
 
 fn load_store_for_static_init() -> Store { // Renamed for clarity of purpose
-    match load_json(&store_path()) { // load_json reads from disk
+    let backend = storage::backend();
+    match load_with_journal(backend.as_ref()) {
         Ok(store) => store,
-        Err(_) => {
-            println!("tasks_store.json not found or failed to load, creating a new one.");
-            let empty_store = Store::new();
-            // Attempt to save this initial empty store.
-            // We can ignore the result here as it's a best-effort for first run.
-            let _ = save_json(&store_path(), &empty_store);
-            empty_store
+        Err(e) => {
+            // the file on disk may still hold our history. with the load error
+            // set, ensure_writable turns away every save and journal write, so
+            // startup's own persisting can't put this empty store over it
+            eprintln!("{} task store failed to load: {}", backend.name(), e);
+            storage::set_load_error(Some(e));
+            Store::new()
         }
     }
 }
@@ -31,28 +55,9 @@ lazy_static! {
     static ref TASK_STORE: TokioMutex<Store> = TokioMutex::new(load_store_for_static_init());
     static ref ACTIVE_TASK_ID: TokioRwLock<Option<TaskId>> = TokioRwLock::new(None);
     static ref ACTIVE_TASK:    TokioMutex<Option<Task>>    = TokioMutex::new(None);
+    static ref INTERRUPTED:    TokioMutex<Option<Interrupted>> = TokioMutex::new(None);
 }
 
-// helper for command_todo
-fn try_parse_date(input: &str) -> Option<String> {
-    let formats = [
-        "%m/%d/%Y",    // 06/01/2025
-        "%m/%d/%y",    // 06/01/25
-        "%Y-%m-%d",    // 2025-06-01 
-        "%m-%d-%Y",    // 06-01-2025
-        "%m-%d-%y",    // 06-01-25
-    ];
-    
-    for format in &formats {
-        if let Ok(date) = NaiveDate::parse_from_str(input, format) {
-            return Some(date.format("%Y-%m-%d").to_string());
-        }
-    }
-    
-    None
-}
-
-
 #[tauri::command]
 pub fn get_current_logical_day_key(days_offset: Option<i64>) -> String {
     let offset = days_offset.unwrap_or(0);
@@ -60,97 +65,493 @@ pub fn get_current_logical_day_key(days_offset: Option<i64>) -> String {
 }
 
 
-async fn persist_global_store() -> Result<(), String> {
+pub(crate) async fn persist_global_store() -> Result<(), String> {
 
     let store_guard = TASK_STORE.lock().await;
     let store_data_to_save = store_guard.clone(); 
     drop(store_guard); 
 
-    tauri::async_runtime::spawn_blocking(move || save_json(&store_path(), &store_data_to_save))
+    save_on_backend(store_data_to_save).await
+}
+
+// journals `change` and, only once it's on disk, applies it to `st` the same
+// way replay would. every change to the store goes through here, so the store
+// never holds anything a restart would lose.
+async fn commit(st: &mut Store, day: &LogicalDay, change: Change) -> Result<(), String> {
+    let ev = journal::append(day, change).await?;
+    // the tasks it touches, as they were and as they are now, for /undo
+    let ids: Vec<TaskId> = match &ev.change {
+        Change::Replaced { bucket } => st.get(day).into_iter().chain(bucket.as_ref())
+            .flat_map(|b| b.todo.keys().chain(b.done.keys()).cloned())
+            .collect(),
+        other => other.task_id().map(|id| id.to_string()).into_iter().collect(),
+    };
+    let before: Vec<Option<Placed>> = ids.iter().map(|id| placed(st, id)).collect();
+    journal::apply(st, &ev);
+    for (id, before) in ids.iter().zip(before) {
+        undo::track_change(id, before, placed(st, id));
+    }
+    Ok(())
+}
+
+// where a task is: its day, which list, and how far down it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Placed {
+    pub day: LogicalDay,
+    pub done: bool,
+    pub pos: usize,
+    pub task: Task,
+}
+
+pub(crate) fn placed(st: &Store, id: &str) -> Option<Placed> {
+    st.iter().find_map(|(day, b)| {
+        let (done, (pos, _, task)) = match b.todo.get_full(id) {
+            Some(hit) => (false, hit),
+            None => (true, b.done.get_full(id)?),
+        };
+        Some(Placed { day: day.clone(), done, pos, task: task.clone() })
+    })
+}
+
+// the same task in the same place, apart from time tracked since
+pub(crate) fn same_place(a: Option<&Placed>, b: Option<&Placed>) -> bool {
+    let strip = |p: &Placed| {
+        let mut t = p.task.clone();
+        t.time_spent = 0;
+        t.sessions.clear();
+        t.untimed = None;
+        (p.day.clone(), p.done, serde_json::to_value(t).ok())
+    };
+    a.map(strip) == b.map(strip)
+}
+
+// folds the journal into a fresh snapshot on the backend. only clones the
+// touched days when the backend can write them on their own.
+pub(crate) async fn compact_store() -> Result<(), String> {
+    storage::ensure_writable()?;
+    let store_guard = TASK_STORE.lock().await;
+    let Some(compaction) = journal::begin_compaction()? else { return Ok(()) };
+    let snapshot: Store = if storage::backend().incremental() {
+        compaction.days.iter()
+            .map(|d| (d.clone(), store_guard.get(d).cloned().unwrap_or_default()))
+            .collect()
+    } else {
+        store_guard.clone()
+    };
+    drop(store_guard);
+
+    let saved = save_on_backend(snapshot).await;
+    journal::end_compaction(compaction, saved)
+}
+
+async fn save_on_backend(st: Store) -> Result<(), String> {
+    storage::ensure_writable()?;
+    let backend = storage::backend();
+    tauri::async_runtime::spawn_blocking(move || backend.save(&st))
         .await
         .map_err(|e| format!("Failed to join save task: {}", e))? 
         .map_err(|e| format!("Failed to save store: {}", e)) 
 }
 
+// swap in whatever the current backend has on disk, e.g. after a restore
+pub(crate) async fn reload_store() -> Result<(), String> {
+    let backend = storage::backend();
+    let fresh = tauri::async_runtime::spawn_blocking(move || load_with_journal(backend.as_ref()))
+        .await
+        .map_err(|e| format!("Failed to join load task: {}", e))?;
+    let fresh = match fresh {
+        Ok(st) => st,
+        Err(e) => {
+            storage::set_load_error(Some(e.clone()));
+            return Err(e);
+        }
+    };
+    *TASK_STORE.lock().await = fresh;
+    storage::set_load_error(None);
+    clear_active_task().await;
+    Ok(())
+}
+
+pub(crate) async fn snapshot_store() -> Store {
+    TASK_STORE.lock().await.clone()
+}
+
+pub(crate) async fn active_task_id() -> Option<TaskId> {
+    ACTIVE_TASK_ID.read().await.clone()
+}
+
+// one task for /undo or /redo to move: from where it should be now to
+// where it was (None = not in the store)
+pub(crate) struct PutBack<'a> {
+    pub id: &'a TaskId,
+    pub now: Option<&'a Placed>,
+    pub to: Option<&'a Placed>,
+}
+
+// puts each task back where `to` has it, leaving everything else on its day
+// alone. refuses if a task isn't where `now` says any more. time already
+// tracked is never taken back: sessions are kept as they are now, and only
+// opened or closed from now on.
+pub(crate) async fn put_back(tasks: &[PutBack<'_>]) -> Result<(), String> {
+    let mut store_guard = TASK_STORE.lock().await;
+    let mut current: HashMap<TaskId, Placed> = HashMap::new();
+    for pb in tasks {
+        let here = placed(&store_guard, pb.id);
+        if !same_place(here.as_ref(), pb.now) {
+            let title = pb.now.or(pb.to).map(|p| p.task.title.clone()).unwrap_or_default();
+            return Err(format!("'{}' has changed since, so it can't be put back", title));
+        }
+        if let Some(p) = here {
+            current.insert(pb.id.clone(), p);
+        }
+    }
+
+    let mut days: IndexMap<LogicalDay, DayBucket> = IndexMap::new();
+    for p in current.values() {
+        let bucket = days.entry(p.day.clone()).or_insert_with(|| store_guard.get(&p.day).cloned().unwrap_or_default());
+        bucket.todo.shift_remove(&p.task.id);
+        bucket.done.shift_remove(&p.task.id);
+    }
+    // top of the list first, so each lands back at its old position
+    let mut targets: Vec<&Placed> = tasks.iter().filter_map(|pb| pb.to).collect();
+    targets.sort_by_key(|p| p.pos);
+    let now = Local::now();
+    for p in targets {
+        let mut task = p.task.clone();
+        if let Some(cur) = current.get(&task.id) {
+            task.sessions = cur.task.sessions.clone();
+            task.untimed = cur.task.untimed;
+            if task.status == TaskStatus::Doing {
+                task.start_session(now);
+            } else {
+                task.stop_session(now);
+            }
+            task.refresh_time(now);
+        }
+        let bucket = days.entry(p.day.clone()).or_insert_with(|| store_guard.get(&p.day).cloned().unwrap_or_default());
+        let list = if p.done { &mut bucket.done } else { &mut bucket.todo };
+        list.shift_insert(p.pos.min(list.len()), task.id.clone(), task);
+    }
+
+    for (day, bucket) in days {
+        commit(&mut store_guard, &day, Change::Replaced { bucket: Some(bucket) }).await?;
+    }
+    Ok(())
+}
+
+// makes `id` the running task again, as long as the store still has it as doing
+pub(crate) async fn restore_active(id: Option<TaskId>) {
+    let task = match id {
+        Some(id) => TASK_STORE.lock().await.values()
+            .find_map(|b| b.todo.get(&id))
+            .filter(|t| t.status == TaskStatus::Doing)
+            .cloned(),
+        None => None,
+    };
+    match task {
+        Some(task) => set_active_task(task).await,
+        None => clear_active_task().await,
+    }
+}
+
 
 // ─── type aliases ────────────────────────────────────────────────────────
-type TaskId      = String;
-type LogicalDay  = String;
+pub(crate) type TaskId      = String;
+pub(crate) type LogicalDay  = String;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskStatus {
+    Todo,
+    Doing,
+    Done,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Task {
     pub id: String,
     pub title: String,
-    pub status: String,     
+    pub status: TaskStatus,
     pub created_at: String, 
     pub time_spent: u64,   
+    // how many times rollover has moved this task onto a later day
+    #[serde(default)]
+    pub carried_over: u32,
+    // the recurring rule that created this task, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recurrence_id: Option<String>,
+    // every /doing -> /break or /done span; time_spent is worked out from these
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sessions: Vec<Session>,
+    // time tracked before sessions were recorded. None on tasks saved by older
+    // builds until load_with_journal fills it in from their time_spent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub untimed: Option<u64>,
+    // #tags and +project from the /todo line, lowercased
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    // 1 (most urgent) to 3, from !1..!3
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<u8>,
+    // expected seconds of work, from ~45m or ~2p
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimate: Option<u64>,
+    // steps added with /sub, checked off with /check
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub checklist: Vec<ChecklistItem>,
+    // ids of the tasks this one waits on. kept once they're done, so
+    // reopening one blocks this again; see open_blockers
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blocked_by: Vec<TaskId>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChecklistItem {
+    pub text: String,
+    #[serde(default)]
+    pub done: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Progress {
+    pub done: usize,
+    pub total: usize,
+}
+
+// a task as get_tasks hands it to the ui
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskView {
+    #[serde(flatten)]
+    pub task: Task,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub progress: Option<Progress>,
+    // waits on something that isn't done yet
+    pub blocked: bool,
+}
+
+impl Task {
+    // a fresh to-do on `day`, with nothing tracked yet
+    pub fn new(title: String, day: LogicalDay) -> Task {
+        Task {
+            id: Uuid::new_v4().to_string(),
+            title,
+            status: TaskStatus::Todo,
+            created_at: day,
+            time_spent: 0,
+            carried_over: 0,
+            recurrence_id: None,
+            sessions: Vec::new(),
+            untimed: Some(0),
+            tags: Vec::new(),
+            project: None,
+            priority: None,
+            estimate: None,
+            checklist: Vec::new(),
+            blocked_by: Vec::new(),
+        }
+    }
+
+    pub fn with_labels(self, labels: Labels) -> Task {
+        Task { tags: labels.tags, project: labels.project, priority: labels.priority, estimate: labels.estimate, ..self }
+    }
+
+    fn open_session(&self) -> Option<&Session> {
+        self.sessions.last().filter(|s| s.end.is_none())
+    }
+
+    pub fn start_session(&mut self, now: DateTime<Local>) {
+        if self.open_session().is_none() {
+            self.sessions.push(Session::open(now));
+        }
+    }
+
+    pub fn stop_session(&mut self, now: DateTime<Local>) {
+        if let Some(s) = self.sessions.last_mut().filter(|s| s.end.is_none()) {
+            s.end = Some(now);
+        }
+        self.refresh_time(now);
+    }
+
+    // None when the task has no checklist
+    pub fn progress(&self) -> Option<Progress> {
+        (!self.checklist.is_empty()).then(|| Progress {
+            done: self.checklist.iter().filter(|i| i.done).count(),
+            total: self.checklist.len(),
+        })
+    }
+
+    pub fn refresh_time(&mut self, now: DateTime<Local>) {
+        self.time_spent = self.untimed.unwrap_or(0) + sessions::total(&self.sessions, now);
+    }
+
+    // closes a session left open by a crash or quit where the last saved
+    // time_spent says it got to, and returns that point
+    fn close_at_last_tick(&mut self) -> Option<DateTime<Local>> {
+        let start = self.open_session()?.start;
+        let counted = self.untimed.unwrap_or(0) + sessions::total(&self.sessions[..self.sessions.len() - 1], start);
+        let secs = self.time_spent.saturating_sub(counted);
+        let end = start + ChronoDuration::seconds(secs as i64);
+        self.stop_session(end);
+        Some(end)
+    }
 }
 
-#[derive(Clone, Serialize, Deserialize, Default)]
-struct DayBucket {
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub(crate) struct DayBucket {
     // perhaps make the hash store a vector of a tuple
     // containing taskid and task
     // perhaps consistent "number" store as well
-    todo: IndexMap<TaskId, Task>,
-    done: IndexMap<TaskId, Task>,
+    pub(crate) todo: IndexMap<TaskId, Task>,
+    pub(crate) done: IndexMap<TaskId, Task>,
 }
 
-type Store = IndexMap<LogicalDay, DayBucket>;
-
+pub(crate) type Store = IndexMap<LogicalDay, DayBucket>;
 
 fn today_key(days_offset: i64) -> LogicalDay {
-    (Local::now() - ChronoDuration::hours(4) - ChronoDuration::days(days_offset))
+    let day_start = config().day_start_hour as i64;
+    (Local::now() - ChronoDuration::hours(day_start) - ChronoDuration::days(days_offset))
         .format("%Y-%m-%d")
         .to_string()
 }
 
-fn data_dir() -> PathBuf {
-    ProjectDirs::from("com", "salieri", "salieri")
-        .map(|d| d.data_local_dir().to_path_buf())
-        .unwrap_or_else(|| PathBuf::from("."))
+// the logical day a moment belongs to, e.g. 1am counts as the night before
+pub(crate) fn logical_day_of(at: DateTime<Local>) -> LogicalDay {
+    (at - ChronoDuration::hours(config().day_start_hour as i64))
+        .format("%Y-%m-%d")
+        .to_string()
 }
 
-fn store_path() -> PathBuf { data_dir().join("tasks_store.json") }
-
-fn ensure_data_dir() {
-    let dir = data_dir();
-    if !dir.exists() { let _ = fs::create_dir_all(&dir); }
+fn bucket_mut<'a>(st: &'a mut Store, day: &LogicalDay) -> &'a mut DayBucket {
+    st.entry(day.clone()).or_default()
 }
 
-fn load_json<T: DeserializeOwned>(p: &Path) -> Result<T, String> {
-    if !p.exists() { return Err("missing".into()); }
-    serde_json::from_str(&fs::read_to_string(p).map_err(|e| e.to_string())?)
-        .map_err(|e| e.to_string())
-}
+// puts the recurring tasks due on `day` into it. runs when the day is first
+// looked at and right after /every, never in the middle of another command,
+// so a command that fails can't leave a rule marked as done for the day
+pub(crate) async fn materialize_day(day: &LogicalDay) -> Result<(), String> {
+    let today = today_key(0);
+    let mut store_guard = TASK_STORE.lock().await;
+    let empty = DayBucket::default();
+    let (tasks, rules) = recurrence::due(store_guard.get(day).unwrap_or(&empty), day, &today, config().daily_limit);
+    if rules.is_empty() {
+        return Ok(());
+    }
+    for task in tasks {
+        commit(&mut store_guard, day, Change::Created { task }).await?;
+    }
+    drop(store_guard);
 
-fn save_json<T: Serialize>(p: &Path, d: &T) -> Result<(), String> {
-    ensure_data_dir();
-    fs::write(p, serde_json::to_string_pretty(d).map_err(|e| e.to_string())?)
-        .map_err(|e| e.to_string())
+    let day = day.clone();
+    tauri::async_runtime::spawn_blocking(move || recurrence::mark_applied(&day, &today, &rules))
+        .await
+        .map_err(|e| format!("Failed to join recurrence save: {}", e))?
+}
+// ─── startup fix 
+// a task that was still running when the app last closed. it starts out
+// paused at the last saved tick; /recover can pick it back up
+#[derive(Debug, Clone, Serialize)]
+pub struct Interrupted {
+    pub day: LogicalDay,
+    pub id: TaskId,
+    pub title: String,
+    pub last_seen: DateTime<Local>,
+    pub gap_secs: u64,
 }
 
-fn load_store() -> Result<Store, String> {
-    load_json(&store_path()).or_else(|_| Ok(Store::new()))
+pub async fn clear_active_startup(h: AppHandle) -> Result<(), String> { 
+    // look at every day, not just today: the day start hour may have moved
+    // since the task was started
+    let mut store_guard = TASK_STORE.lock().await;
+    let mut paused = Vec::new();
+    let mut latest: Option<Interrupted> = None;
+    for (day, bucket) in store_guard.iter() {
+        for t in bucket.todo.values().filter(|t| t.status == TaskStatus::Doing) {
+            let mut t = t.clone();
+            t.status = TaskStatus::Todo;
+            if let Some(last_seen) = t.close_at_last_tick() {
+                if latest.as_ref().is_none_or(|l| l.last_seen < last_seen) {
+                    let gap_secs = (Local::now() - last_seen).num_seconds().max(0) as u64;
+                    latest = Some(Interrupted { day: day.clone(), id: t.id.clone(), title: t.title.clone(), last_seen, gap_secs });
+                }
+            }
+            paused.push((day.clone(), t));
+        }
+    }
+    for (day, task) in paused {
+        commit(&mut store_guard, &day, Change::Paused { task }).await?;
+    }
+    drop(store_guard);
+
+    let max_gap = config().max_resume_gap_minutes * 60;
+    if let Some(int) = latest.filter(|i| i.gap_secs <= max_gap) {
+        let _ = h.emit("task_interrupted", int.clone());
+        *INTERRUPTED.lock().await = Some(int);
+    }
+    Ok(())
 }
 
-fn save_store(st: &Store) -> Result<(), String> {
-    save_json(&store_path(), st)
+#[tauri::command]
+pub async fn get_interrupted_task() -> Option<Interrupted> {
+    INTERRUPTED.lock().await.clone()
 }
 
-fn bucket_mut<'a>(st: &'a mut Store, day: &LogicalDay) -> &'a mut DayBucket {
-    st.entry(day.clone()).or_insert_with(DayBucket::default)
+// "45s", "12m", "2h 05m"; shared by the reports and exports
+pub(crate) fn format_duration(secs: u64) -> String {
+    match secs {
+        s if s < 60 => format!("{}s", s),
+        s if s < 3600 => format!("{}m", s / 60),
+        s => format!("{}h {:02}m", s / 3600, (s % 3600) / 60),
+    }
 }
-// ─── startup fix 
-pub async fn clear_active_startup(_h: AppHandle) -> Result<(), String> { 
-    let mut store_guard = TASK_STORE.lock().await;
-    if let Some(bucket) = store_guard.get_mut(&today_key(0)) {
-        for t in bucket.todo.values_mut() {
-            if t.status == "doing" { t.status = "todo".into(); }
+
+// ─── /recover [resume|credit|discard]
+// resume: running again from now. credit: running again, and the time the app
+// was closed counts as worked. discard: leave it paused where it was.
+pub async fn command_recover(parts: &[&str], _app: AppHandle) -> Result<String, String> {
+    let mut pending = INTERRUPTED.lock().await;
+    let Some(int) = pending.clone() else {
+        return Err("no interrupted task to recover".into());
+    };
+
+    let credit = match parts.get(1) {
+        None => return Ok(format!(
+            "'{}' was running when salieri closed, {} ago. /recover resume, credit or discard",
+            int.title, format_duration((Local::now() - int.last_seen).num_seconds().max(0) as u64)
+        )),
+        Some(&"discard") => {
+            *pending = None;
+            return Ok(format!("left '{}' paused", int.title));
         }
+        Some(&"resume") => false,
+        Some(&"credit") => true,
+        Some(arg) => return Err(format!("unknown /recover argument '{arg}'. use resume, credit, or discard.")),
+    };
+
+    if ACTIVE_TASK_ID.read().await.is_some() {
+        return Err("another task is running. /break it first".into());
+    }
+    let mut store_guard = TASK_STORE.lock().await;
+    let mut task = store_guard.get(&int.day)
+        .and_then(|b| b.todo.get(&int.id))
+        .cloned()
+        .ok_or_else(|| format!("'{}' is no longer in the to-do list", int.title))?;
+
+    let now = Local::now();
+    task.status = TaskStatus::Doing;
+    match task.sessions.last_mut().filter(|_| credit) {
+        // reopening the session counts everything since it started
+        Some(s) => s.end = None,
+        None => task.start_session(now),
     }
+    task.refresh_time(now);
+    commit(&mut store_guard, &int.day, Change::Started { task: task.clone() }).await?;
     drop(store_guard);
-    persist_global_store().await?;
-    Ok(())
+
+    set_active_task(task).await;
+    *pending = None;
+    Ok(if credit { format!("'{}' resumed, time away counted", int.title) } else { format!("'{}' resumed", int.title) })
 }
 
 async fn clear_active_task() {
@@ -173,85 +574,623 @@ async fn set_active_task(task: Task) {
 }
 
 // ─── timer loop 
-pub fn start_task_timer_loop(_h: AppHandle) { 
+#[derive(Debug, Clone, Serialize)]
+pub struct OverEstimate {
+    pub id: TaskId,
+    pub title: String,
+    pub estimate: u64,
+    pub time_spent: u64,
+}
+
+pub fn start_task_timer_loop(h: AppHandle) { 
     tokio::spawn(async move { 
         let mut ticker = tokio::time::interval(Duration::from_secs(1));
         let mut tick_count = 0u64; 
+        let mut current_day = today_key(0);
         loop {
             ticker.tick().await;
             tick_count = tick_count.wrapping_add(1);
 
+            // crossing into a new logical day
+            let now_day = today_key(0);
+            if now_day != current_day {
+                current_day = now_day;
+                if config().auto_rollover {
+                    match rollover(&current_day).await {
+                        Ok(report) => { let _ = h.emit("tasks_rolled_over", report); }
+                        Err(e) => eprintln!("automatic rollover failed: {}", e),
+                    }
+                }
+            }
+
             let active_id_opt = ACTIVE_TASK_ID.read().await.clone();
             let Some(id) = active_id_opt else { continue }; 
 
             let mut store_guard = TASK_STORE.lock().await;
             let today = today_key(0);
-            let mut changed_in_loop = false;
+            let mut ticked = None;
+            let mut over = None;
             if let Some(bucket) = store_guard.get_mut(&today) {
                 if let Some(task) = bucket.todo.get_mut(&id) {
-                    if task.status == "doing" {
-                        task.time_spent += 1;
-                        changed_in_loop = true;
+                    if task.status == TaskStatus::Doing {
+                        let before = task.time_spent;
+                        task.refresh_time(Local::now());
+                        ticked = Some(task.time_spent);
+                        // only on the tick that goes past it
+                        if task.estimate.is_some_and(|e| before <= e && task.time_spent > e) {
+                            over = Some(OverEstimate { id: id.clone(), title: task.title.clone(), estimate: task.estimate.unwrap_or(0), time_spent: task.time_spent });
+                        }
                     }
                 }
             }
             drop(store_guard); 
 
-            if changed_in_loop && tick_count % 60 == 0 { 
-                if let Err(e) = persist_global_store().await {
-                    eprintln!("Timer loop failed to save store: {}", e);
+            if let Some(over) = over {
+                let _ = h.emit("over_estimate", over);
+            }
+
+            if tick_count % 60 == 0 { 
+                if let Some(time_spent) = ticked {
+                    if let Err(e) = journal::append(&today, Change::Ticked { id, time_spent }).await {
+                        eprintln!("Timer loop failed to journal time: {}", e);
+                    }
+                }
+                if journal::needs_compaction() {
+                    if let Err(e) = compact_store().await {
+                        eprintln!("Timer loop failed to compact the journal: {}", e);
+                    }
                 }
             }
         }
     });
 }
 
+// ─── rollover
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct RolloverReport {
+    pub day: LogicalDay,
+    pub moved: Vec<String>,
+    pub left_behind: Vec<LeftBehind>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LeftBehind {
+    pub day: LogicalDay,
+    pub title: String,
+    pub reason: String,
+}
+
+impl RolloverReport {
+    fn summary(&self) -> String {
+        if self.moved.is_empty() && self.left_behind.is_empty() {
+            return "nothing to carry over".into();
+        }
+        let mut out = format!("carried {} task(s) over to {}", self.moved.len(), self.day);
+        if !self.moved.is_empty() {
+            out.push_str(&format!(": {}", self.moved.join(", ")));
+        }
+        for lb in &self.left_behind {
+            out.push_str(&format!("\nleft '{}' on {}: {}", lb.title, lb.day, lb.reason));
+        }
+        out
+    }
+}
+
+// works out which unfinished tasks from earlier days go onto `today`, oldest
+// first, until today's list is full. returns the report and each task to move
+// as it will be on today, with the day it comes from.
+fn plan_rollover(st: &Store, today: &LogicalDay, limit: usize) -> (RolloverReport, Vec<(LogicalDay, Task)>) {
+    let mut report = RolloverReport { day: today.clone(), ..Default::default() };
+    let mut earlier: Vec<&LogicalDay> = st.keys()
+        .filter(|d| d.as_str() < today.as_str())
+        .filter(|d| !st[*d].todo.is_empty())
+        .collect();
+    earlier.sort();
+
+    let target = st.get(today);
+    let mut titles: Vec<&str> = target.iter()
+        .flat_map(|b| b.todo.values().chain(b.done.values()))
+        .map(|t| t.title.as_str())
+        .collect();
+    let mut pending = target.map_or(0, |b| b.todo.len());
+    let mut moves = Vec::new();
+    for day in earlier {
+        for t in st[day].todo.values() {
+            let reason = if titles.contains(&t.title.as_str()) {
+                Some("a task with that title is already on today".to_string())
+            } else if pending >= limit {
+                Some(format!("today is full ({} tasks)", limit))
+            } else {
+                None
+            };
+            if let Some(reason) = reason {
+                report.left_behind.push(LeftBehind { day: day.clone(), title: t.title.clone(), reason });
+                continue;
+            }
+
+            let mut task = t.clone();
+            task.carried_over += 1;
+            report.moved.push(task.title.clone());
+            titles.push(&t.title);
+            pending += 1;
+            moves.push((day.clone(), task));
+        }
+    }
+    (report, moves)
+}
+
+async fn rollover(today: &LogicalDay) -> Result<RolloverReport, String> {
+    let mut store_guard = TASK_STORE.lock().await;
+    let (report, moves) = plan_rollover(&store_guard, today, config().daily_limit);
+    for (from, task) in moves {
+        commit(&mut store_guard, today, Change::Moved { task, from }).await?;
+    }
+    Ok(report)
+}
+
+// run once at launch in case the app was closed over a day boundary
+pub async fn rollover_startup(h: AppHandle) -> Result<(), String> {
+    if !config().auto_rollover {
+        return Ok(());
+    }
+    let report = rollover(&today_key(0)).await?;
+    if !report.moved.is_empty() || !report.left_behind.is_empty() {
+        println!("{}", report.summary());
+        let _ = h.emit("tasks_rolled_over", report);
+    }
+    Ok(())
+}
+
+// ─── /rollover
+pub async fn command_rollover(_parts: &[&str], _app: AppHandle) -> Result<String, String> {
+    rollover(&today_key(0)).await.map(|r| r.summary())
+}
 
 // ─── query API 
 #[tauri::command]
-pub async fn get_tasks(_h: AppHandle, day: String, done: bool) -> Result<Vec<Task>, String> { 
-    let store_guard = TASK_STORE.lock().await; 
+pub async fn get_tasks(_h: AppHandle, day: String, done: bool, tag: Option<String>, project: Option<String>, by_priority: Option<bool>) -> Result<Vec<TaskView>, String> { 
+    // first look at a day is when its recurring tasks show up
+    if let Err(e) = materialize_day(&day).await {
+        eprintln!("couldn't add recurring tasks to {}: {}", day, e);
+    }
+    let store_guard = TASK_STORE.lock().await;
     let bucket = store_guard.get(&day).cloned().unwrap_or_default();
-    Ok(if done { bucket.done } else { bucket.todo }
+    let tags: Vec<String> = tag.iter().map(|t| t.trim_start_matches('#').to_lowercase()).collect();
+    let project = project.map(|p| p.trim_start_matches('+').to_lowercase());
+    let mut list: Vec<Task> = if done { bucket.done } else { bucket.todo }
         .into_values()
+        .filter(|t| t.has_labels(&tags, project.as_deref()))
+        .collect();
+    if by_priority.unwrap_or(false) {
+        // !1 first, unprioritized last, otherwise as they were
+        list.sort_by_key(|t| t.priority.unwrap_or(u8::MAX));
+    }
+    Ok(list.into_iter().map(|task| TaskView { progress: task.progress(), blocked: !open_blockers(&store_guard, &task).is_empty(), task }).collect())
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HourTotal {
+    pub date: String,
+    pub hour: u32,
+    pub seconds: u64,
+}
+
+// when the day's tasks were worked on, hour by hour of the wall clock
+#[tauri::command]
+pub async fn get_time_by_hour(day: String) -> Result<Vec<HourTotal>, String> {
+    let store_guard = TASK_STORE.lock().await;
+    let Some(bucket) = store_guard.get(&day) else { return Ok(Vec::new()) };
+    let now = Local::now();
+    let mut totals = std::collections::BTreeMap::new();
+    for t in bucket.todo.values().chain(bucket.done.values()) {
+        for (slot, secs) in sessions::by_hour(&t.sessions, now) {
+            *totals.entry(slot).or_insert(0) += secs;
+        }
+    }
+    Ok(totals.into_iter()
+        .map(|((date, hour), seconds)| HourTotal { date: date.format("%Y-%m-%d").to_string(), hour, seconds })
         .collect())
 }
 
+// ─── task lookup
+// resolves what the user typed after a command to a task in `list`, trying in
+// order: exact title, 1-based position ("/done 2"), unique id prefix
+// ("/done 3fa1"), title ignoring case, substring of a title, and finally a
+// fuzzy match. Ok(None) means nothing came close; Err carries the candidates
+// when more than one task fits.
+const MIN_ID_PREFIX: usize = 4;
+// fuzzy scores needed to pick a task outright, or to suggest it
+const FUZZY_ACCEPT: f64 = 0.7;
+const FUZZY_SUGGEST: f64 = 0.4;
+// how far ahead of the runner-up a fuzzy winner has to be
+const FUZZY_MARGIN: f64 = 0.1;
+
+// the tasks a lookup couldn't choose between, best first
+#[derive(Debug, Clone, Serialize)]
+pub struct Candidates {
+    pub query: String,
+    pub tasks: Vec<TaskSuggestion>,
+    // none of them actually contains what was typed; they're just close
+    pub close_only: bool,
+}
+
+impl Candidates {
+    fn new(list: &IndexMap<TaskId, Task>, query: &str, ids: &[(&TaskId, f64)], close_only: bool) -> Candidates {
+        let tasks = ids.iter()
+            .filter_map(|(id, score)| {
+                let (pos, _, t) = list.get_full(*id)?;
+                Some(TaskSuggestion { id: t.id.clone(), title: t.title.clone(), position: pos + 1, score: *score })
+            })
+            .collect();
+        Candidates { query: query.to_string(), tasks, close_only }
+    }
+}
+
+impl From<Candidates> for String {
+    fn from(c: Candidates) -> String {
+        let list = c.tasks.iter()
+            .map(|t| format!("'{}' ({})", t.title, t.position))
+            .collect::<Vec<_>>()
+            .join(", ");
+        if c.close_only {
+            format!("no task '{}'. did you mean {}?", c.query, list)
+        } else {
+            format!("'{}' could be any of: {}", c.query, list)
+        }
+    }
+}
+
+// the todo list first, then done. when neither has it, the todo list's
+// candidates win. plain numbers are only ever to-do positions; "d2" is the
+// second task on the done list
+fn find_in_bucket(bucket: &DayBucket, key: &str) -> Result<Option<TaskId>, String> {
+    if let Some((id, _)) = bucket.todo.iter().chain(bucket.done.iter()).find(|(_, t)| t.title == key) {
+        return Ok(Some(id.clone()));
+    }
+    let position = |list: &IndexMap<TaskId, Task>, n: &str, name: &str| -> Option<Result<Option<TaskId>, String>> {
+        let n = n.parse::<usize>().ok()?;
+        Some(match list.get_index(n.wrapping_sub(1)) {
+            Some((id, _)) => Ok(Some(id.clone())),
+            None => Err(format!("there's no task {} on the {} list; it has {}", n, name, list.len())),
+        })
+    };
+    if let Some(found) = key.strip_prefix('d').and_then(|n| position(&bucket.done, n, "done")) {
+        return found;
+    }
+    if let Some(found) = position(&bucket.todo, key, "to-do") {
+        return found;
+    }
+    match find_task(&bucket.todo, key) {
+        Ok(Some(id)) => Ok(Some(id)),
+        in_todo => match find_task(&bucket.done, key)? {
+            Some(id) => Ok(Some(id)),
+            None => in_todo.map_err(String::from),
+        },
+    }
+}
+
+// only what can't pick the wrong task: exact title, position, or id prefix.
+// for commands that are hard to take back
+fn find_exact(list: &IndexMap<TaskId, Task>, key: &str) -> Result<Option<TaskId>, Candidates> {
+    if let Some((id, _)) = list.iter().find(|(_, t)| t.title == key) {
+        return Ok(Some(id.clone()));
+    }
+
+    if let Ok(n) = key.parse::<usize>() {
+        if n >= 1 && n <= list.len() {
+            return Ok(list.get_index(n - 1).map(|(id, _)| id.clone()));
+        }
+    }
+
+    let lower = key.to_lowercase();
+    if lower.len() >= MIN_ID_PREFIX && lower.chars().all(|c| c.is_ascii_hexdigit() || c == '-') {
+        let hits: Vec<(&TaskId, f64)> = list.keys().filter(|id| id.starts_with(&lower)).map(|id| (id, 1.0)).collect();
+        match hits.as_slice() {
+            [] => {}
+            [(id, _)] => return Ok(Some((*id).clone())),
+            many => return Err(Candidates::new(list, key, many, false)),
+        }
+    }
+    Ok(None)
+}
+
+fn find_task(list: &IndexMap<TaskId, Task>, key: &str) -> Result<Option<TaskId>, Candidates> {
+    if let Some(id) = find_exact(list, key)? {
+        return Ok(Some(id));
+    }
+
+    let lower = key.to_lowercase();
+    if let Some((id, _)) = list.iter().find(|(_, t)| t.title.to_lowercase() == lower) {
+        return Ok(Some(id.clone()));
+    }
+
+    let containing: Vec<(&TaskId, f64)> = list.iter()
+        .filter(|(_, t)| t.title.to_lowercase().contains(&lower))
+        .map(|(id, t)| (id, fuzzy::similarity(key, &t.title)))
+        .collect();
+    match containing.as_slice() {
+        [] => {}
+        [(id, _)] => return Ok(Some((*id).clone())),
+        many => return Err(Candidates::new(list, key, many, false)),
+    }
+
+    let candidates: Vec<(TaskId, String)> = list.iter().map(|(id, t)| (id.clone(), t.title.clone())).collect();
+    let ranked = fuzzy::rank(key, &candidates);
+    let best = ranked.first().map(|(_, s)| *s).unwrap_or(0.0);
+    let runner_up = ranked.get(1).map(|(_, s)| *s).unwrap_or(0.0);
+    if best >= FUZZY_ACCEPT && best - runner_up >= FUZZY_MARGIN {
+        return Ok(ranked.into_iter().next().map(|(id, _)| id));
+    }
+
+    let close: Vec<(&TaskId, f64)> = ranked.iter()
+        .filter(|(_, s)| *s >= FUZZY_SUGGEST)
+        .take(3)
+        .map(|(id, s)| (id, *s))
+        .collect();
+    if close.is_empty() {
+        Ok(None)
+    } else {
+        Err(Candidates::new(list, key, &close, true))
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskSuggestion {
+    pub id: TaskId,
+    pub title: String,
+    // 1-based, as typed in "/done 2"
+    pub position: usize,
+    pub score: f64,
+}
+
+// launcher-style completions for the palette, best match first
+#[tauri::command]
+pub async fn suggest_tasks(day: String, query: String, done: Option<bool>) -> Result<Vec<TaskSuggestion>, String> {
+    let store_guard = TASK_STORE.lock().await;
+    let Some(bucket) = store_guard.get(&day) else { return Ok(Vec::new()) };
+    let list = if done.unwrap_or(false) { &bucket.done } else { &bucket.todo };
+
+    let candidates: Vec<(TaskId, String)> = list.iter().map(|(id, t)| (id.clone(), t.title.clone())).collect();
+    let lower = query.to_lowercase();
+    let mut out: Vec<TaskSuggestion> = fuzzy::rank(&query, &candidates)
+        .into_iter()
+        .filter_map(|(id, score)| {
+            let (pos, _, t) = list.get_full(&id)?;
+            // substring hits always make the list, ahead of pure fuzzy ones
+            let score = if t.title.to_lowercase().contains(&lower) { score.max(FUZZY_ACCEPT) } else { score };
+            (score >= FUZZY_SUGGEST).then(|| TaskSuggestion { id, title: t.title.clone(), position: pos + 1, score })
+        })
+        .collect();
+    out.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+    Ok(out)
+}
+
+// ─── search
+impl Task {
+    // everything /find looks at
+    pub fn search_text(&self) -> String {
+        let mut text = self.title.clone();
+        text.push_str(&self.labels());
+        for item in &self.checklist {
+            text.push(' ');
+            text.push_str(&item.text);
+        }
+        text
+    }
+
+    // " !1 #tag +project ~45m" as far as the task has them, or empty
+    pub fn labels(&self) -> String {
+        let mut out = String::new();
+        if let Some(p) = self.priority {
+            out.push_str(&format!(" !{}", p));
+        }
+        for t in &self.tags {
+            out.push_str(&format!(" #{}", t));
+        }
+        if let Some(p) = &self.project {
+            out.push_str(&format!(" +{}", p));
+        }
+        if let Some(e) = self.estimate {
+            out.push_str(&format!(" ~{}", labels::format_estimate(e)));
+        }
+        out
+    }
+
+    // has every tag in `tags`, and `project` if one is given
+    pub fn has_labels(&self, tags: &[String], project: Option<&str>) -> bool {
+        tags.iter().all(|t| self.tags.contains(t)) && project.is_none_or(|p| self.project.as_deref() == Some(p))
+    }
+}
+
+impl TaskStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TaskStatus::Todo => "todo",
+            TaskStatus::Doing => "doing",
+            TaskStatus::Done => "done",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchHit {
+    pub day: LogicalDay,
+    pub id: TaskId,
+    pub title: String,
+    pub status: TaskStatus,
+    pub time_spent: u64,
+    pub score: f64,
+}
+
+// every day in the store. a task matches when it contains all the words of
+// the query, or failing that, when it's a close fuzzy match for it.
+// best first, and newest first among equals.
+fn search_store(st: &Store, query: &str) -> Vec<SearchHit> {
+    let words: Vec<String> = query.split_whitespace().map(|w| w.to_lowercase()).collect();
+    if words.is_empty() {
+        return Vec::new();
+    }
+    let mut hits: Vec<SearchHit> = st.iter()
+        .flat_map(|(day, b)| b.todo.values().chain(b.done.values()).map(move |t| (day, t)))
+        .filter_map(|(day, t)| {
+            let text = t.search_text();
+            let lower = text.to_lowercase();
+            let score = if words.iter().all(|w| lower.contains(w.as_str())) {
+                1.0
+            } else {
+                fuzzy::similarity(query, &text)
+            };
+            (score >= FUZZY_ACCEPT).then(|| SearchHit {
+                day: day.clone(),
+                id: t.id.clone(),
+                title: t.title.clone(),
+                status: t.status,
+                time_spent: t.time_spent,
+                score,
+            })
+        })
+        .collect();
+    hits.sort_by(|a, b| {
+        b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal).then_with(|| b.day.cmp(&a.day))
+    });
+    hits
+}
+
+#[tauri::command]
+pub async fn search_tasks(query: String, limit: Option<usize>) -> Result<Vec<SearchHit>, String> {
+    let mut hits = search_store(&*TASK_STORE.lock().await, &query);
+    if let Some(n) = limit {
+        hits.truncate(n);
+    }
+    Ok(hits)
+}
+
+// ─── /find <query>
+const FIND_SHOWN: usize = 20;
+
+pub async fn command_find(parts: &[&str], _app: AppHandle) -> Result<String, String> {
+    let query = parts.get(1..).map(|p| p.join(" ")).filter(|q| !q.is_empty()).ok_or("usage: /find <query>")?;
+    let hits = search_store(&*TASK_STORE.lock().await, &query);
+    if hits.is_empty() {
+        return Ok(format!("nothing matches '{}'", query));
+    }
+    let mut out: Vec<String> = hits.iter()
+        .take(FIND_SHOWN)
+        .map(|h| format!("{} [{}] {} {}", h.day, h.status.as_str(), h.title, format_duration(h.time_spent)))
+        .collect();
+    if hits.len() > FIND_SHOWN {
+        out.push(format!("...and {} more", hits.len() - FIND_SHOWN));
+    }
+    Ok(out.join("\n"))
+}
+
+// ─── imports
+pub(crate) enum ImportSkip {
+    Duplicate,
+    OverLimit,
+}
+
+// adds tasks read by /import under the same rules as /todo: no repeated title
+// on a day, and no more unfinished tasks than the daily limit. returns the
+// ones left out, in order.
+pub(crate) async fn add_imported(tasks: Vec<(LogicalDay, Task)>) -> Result<Vec<(LogicalDay, String, ImportSkip)>, String> {
+    storage::ensure_writable()?;
+    let limit = config().daily_limit;
+    let mut skipped = Vec::new();
+    let mut store_guard = TASK_STORE.lock().await;
+    for (day, task) in tasks {
+        let bucket = bucket_mut(&mut store_guard, &day);
+        if bucket.todo.values().chain(bucket.done.values()).any(|t| t.title == task.title) {
+            skipped.push((day, task.title, ImportSkip::Duplicate));
+            continue;
+        }
+        if task.status != TaskStatus::Done && bucket.todo.len() >= limit {
+            skipped.push((day, task.title, ImportSkip::OverLimit));
+            continue;
+        }
+        commit(&mut store_guard, &day, Change::Created { task }).await?;
+    }
+    Ok(skipped)
+}
+
+// ─── /tasks #tag +project
+// every task with all the given labels, newest day first. with no labels,
+// lists the ones in use
+pub async fn command_tasks(parts: &[&str], _app: AppHandle) -> Result<String, String> {
+    let st = TASK_STORE.lock().await;
+
+    if parts.len() < 2 {
+        let mut counts: IndexMap<String, usize> = IndexMap::new();
+        for t in st.values().flat_map(|b| b.todo.values().chain(b.done.values())) {
+            for label in t.labels().split_whitespace() {
+                *counts.entry(label.to_string()).or_insert(0) += 1;
+            }
+        }
+        if counts.is_empty() {
+            return Ok("no tags or projects yet. add them with /todo fix login #backend +auth".into());
+        }
+        counts.sort_by(|a, _, b, _| a.cmp(b));
+        return Ok(counts.iter().map(|(l, n)| format!("{} ({})", l, n)).collect::<Vec<_>>().join("\n"));
+    }
+
+    let mut tags = Vec::new();
+    let mut project = None;
+    for w in &parts[1..] {
+        match (labels::tag(w), labels::project(w)) {
+            (Some(t), _) => tags.push(t),
+            (_, Some(p)) => project = Some(p),
+            _ => return Err(format!("'{}' isn't a #tag or +project. usage: /tasks #tag +project", w)),
+        }
+    }
+
+    let mut hits: Vec<(&LogicalDay, &Task)> = st.iter()
+        .flat_map(|(day, b)| b.todo.values().chain(b.done.values()).map(move |t| (day, t)))
+        .filter(|(_, t)| t.has_labels(&tags, project.as_deref()))
+        .collect();
+    if hits.is_empty() {
+        return Ok(format!("no tasks with {}", parts[1..].join(" ")));
+    }
+    hits.sort_by(|a, b| b.0.cmp(a.0));
+    let mut out: Vec<String> = hits.iter()
+        .take(FIND_SHOWN)
+        .map(|(day, t)| format!("{} [{}] {}{}", day, t.status.as_str(), t.title, t.labels()))
+        .collect();
+    if hits.len() > FIND_SHOWN {
+        out.push(format!("...and {} more", hits.len() - FIND_SHOWN));
+    }
+    Ok(out.join("\n"))
+}
+
 // ─── macro 
 macro_rules! ensure_title { ($p:expr) => { if $p.len() < 2 { return Err("need task title".into()); } }; }
 
 // ─── /todo 
-pub async fn command_todo(parts: &[&str], _app: AppHandle) -> Result<String, String> {
+pub async fn command_todo(parts: &[&str], _app: AppHandle, days_offset: Option<i64>) -> Result<String, String> {
     ensure_title!(parts);
-    let mut title = parts[1..].join(" ");
-    let mut day = today_key(0);
-    if parts.len() > 1 {
-        if let Some(parsed_date) = try_parse_date(parts[1]) {
-            day = parsed_date;
-            title = parts[2..].join(" ");
-        }
-    }
+    // the day on screen; "tomorrow" and "+3d" count from it too
+    let viewed = today_key(days_offset.unwrap_or(0));
+    let viewed_date = NaiveDate::parse_from_str(&viewed, "%Y-%m-%d").map_err(|e| e.to_string())?;
+    // labels come out first so "+3d" is still there for the date
+    let (words, labels) = labels::extract_labels(&parts[1..]);
+    let (date, words) = dateparse::extract_date(&words, viewed_date);
+    let title = words.join(" ");
+    let day = date.map(|d| d.format("%Y-%m-%d").to_string()).unwrap_or_else(|| viewed.clone());
     let mut store_guard = TASK_STORE.lock().await; 
     let bucket = bucket_mut(&mut *store_guard, &day); 
-    if bucket.todo.values().len()  == 5 {
-        return Err("No more than five tasks per day.".into())
+    let limit = config().daily_limit;
+    if bucket.todo.values().len() >= limit {
+        return Err(format!("No more than {} tasks per day.", limit))
     }
     if bucket.todo.values().any(|t| t.title == title) || bucket.done.values().any(|t| t.title == title) {
         return Err("duplicate title".into());
     }
 
-    let task = Task { id: Uuid::new_v4().to_string(), title: title.clone(), status: "todo".into(), created_at: day.clone(), time_spent: 0 };
-    bucket.todo.insert(task.id.clone(), task);
+    let task = Task::new(title.clone(), day.clone()).with_labels(labels);
+    commit(&mut store_guard, &day, Change::Created { task }).await?;
 
-    let store_data_to_save = store_guard.clone(); 
     drop(store_guard); 
 
-    tauri::async_runtime::spawn_blocking(move || save_json(&store_path(), &store_data_to_save))
-        .await
-        .map_err(|e| format!("Failed to join save task: {}", e))? 
-        .map_err(|e| format!("Failed to save store: {}", e))?;
-
-    Ok("added".into())
+    if day == viewed { Ok("added".into()) } else { Ok(format!("added to {}", day)) }
 
 
 }
@@ -266,43 +1205,40 @@ pub async fn command_doing(parts: &[&str], _app: AppHandle, days_offset: Option<
     let day = today_key(offset);
     let bucket = bucket_mut(&mut *store_guard, &day);
 
-    let old_id_opt = ACTIVE_TASK_ID.read().await.clone();
-
-    let task_to_activate_details = bucket.todo.iter_mut()
-        .find(|(_, t)| t.title == title)
-        .map(|(id, task_ref)| {
-            if task_ref.status == "doing" {
-                return Err("already active".to_string());
-            }
-            task_ref.status = "doing".into();
-            Ok((id.clone(), task_ref.clone()))
-        });
-
-    let (task_id_to_activate, task_object_to_set_active) = match task_to_activate_details {
-        Some(Ok(details)) => details,
-        Some(Err(e)) => {
-            drop(store_guard);
-            return Err(e);
-        }
-        None => {
-            drop(store_guard);
-            return Err("task not found".into());
-        }
+    let Some(mut task) = find_task(&bucket.todo, &title)?.and_then(|id| bucket.todo.get(&id)).cloned() else {
+        return Err("task not found".into());
     };
-
-    clear_active_task().await;
-    set_active_task(task_object_to_set_active).await;
-
-    if let Some(old_id_val) = old_id_opt {
-        if old_id_val != task_id_to_activate {
-            if let Some(t) = bucket.todo.get_mut(&old_id_val) {
-                t.status = "todo".into();
-            }
+    // a task waiting on others can't be started
+    let open = open_blockers(&store_guard, &task);
+    if !open.is_empty() {
+        let mut msg = format!("'{}' is waiting on {}. finish that first, or /unblock it", task.title, blocker_titles(&store_guard, &open));
+        if open.iter().any(|b| locate(&store_guard, b).is_none()) {
+            msg.push_str(&format!(". /unblock {} from missing drops the missing ones", task.title));
         }
+        return Err(msg);
+    }
+    if task.status == TaskStatus::Doing {
+        return Err("already active".into());
     }
 
+    let now = Local::now();
+    let old_id_opt = ACTIVE_TASK_ID.read().await.clone();
+    if let Some(old_id_val) = old_id_opt.filter(|old| *old != task.id) {
+        // the old task may be on another day
+        let old = store_guard.iter().find_map(|(d, b)| b.todo.get(&old_id_val).map(|t| (d.clone(), t.clone())));
+        if let Some((old_day, mut t)) = old {
+            t.status = TaskStatus::Todo;
+            t.stop_session(now);
+            commit(&mut store_guard, &old_day, Change::Paused { task: t }).await?;
+        }
+    }
+    task.status = TaskStatus::Doing;
+    task.start_session(now);
+    commit(&mut store_guard, &day, Change::Started { task: task.clone() }).await?;
     drop(store_guard);
-    persist_global_store().await?;
+
+    clear_active_task().await;
+    set_active_task(task).await;
 
     Ok("task active".into())
 }
@@ -317,51 +1253,54 @@ pub async fn command_done(parts: &[&str], h: AppHandle, days_offset: Option<i64>
     let day = today_key(offset);
     let bucket = bucket_mut(&mut *store_guard, &day); 
 
-    let task_id_opt = bucket.todo.iter()
-        .find(|(_, t)| t.title == title)
-        .map(|(id, _)| id.clone());
-
-    let task_id = match task_id_opt {
+    let task_id = match find_task(&bucket.todo, &title)? {
         Some(id) => id,
         None => return Err("task not found".into()),
     };
-    if let Some(mut task) = bucket.todo.shift_remove(&task_id) {
-        task.status = "done".into();
-        bucket.done.insert(task_id.clone(), task);
-
-        let current_active_id_opt = ACTIVE_TASK_ID.read().await.clone();
-        if current_active_id_opt.as_deref() == Some(&task_id) {
-            clear_active_task().await; 
-        }
+    let freed = complete_task(&mut store_guard, &day, &task_id, h).await?;
+    drop(store_guard); 
 
-        increment_tasks_done(h); 
+    Ok(format!("task moved to done{}", describe_freed(&freed)))
+}
 
-        drop(store_guard); 
-        persist_global_store().await?; 
+// moves a todo task to the done list, as /done and a finished checklist do.
+// returns the titles of tasks that were only waiting on it
+async fn complete_task(st: &mut Store, day: &LogicalDay, task_id: &TaskId, h: AppHandle) -> Result<Vec<String>, String> {
+    let Some(mut task) = st.get(day).and_then(|b| b.todo.get(task_id)).cloned() else {
+        return Err("task found by ID but could not be removed".into());
+    };
+    task.status = TaskStatus::Done;
+    task.stop_session(Local::now());
+    commit(st, day, Change::Completed { task }).await?;
 
-        Ok("task moved to done".into())
-    } else {
-        Err("task found by ID but could not be removed".into())
+    let current_active_id_opt = ACTIVE_TASK_ID.read().await.clone();
+    if current_active_id_opt.as_deref() == Some(task_id) {
+        clear_active_task().await; 
     }
+
+    increment_tasks_done(h); 
+    Ok(freed_by(st, task_id))
 }
 
 // ─── /break 
-// add offset logic
-pub async fn command_break(parts: &[&str], _app: AppHandle) -> Result<String, String> { 
+pub async fn command_break(parts: &[&str], _app: AppHandle, days_offset: Option<i64>) -> Result<String, String> { 
     ensure_title!(parts);
     let title = parts[1..].join(" ");
 
     let mut store_guard = TASK_STORE.lock().await;
-    let day = today_key(0);
+    let day = today_key(days_offset.unwrap_or(0));
     let bucket = bucket_mut(&mut *store_guard, &day); 
 
-    if let Some(task) = bucket.todo.values_mut().find(|t| t.title == title) {
-        if task.status == "doing" {
-            task.status = "todo".into(); 
+    let found = find_task(&bucket.todo, &title)?;
+    if let Some(mut task) = found.and_then(|id| bucket.todo.get(&id)).cloned() {
+        let title = task.title.clone();
+        if task.status == TaskStatus::Doing {
+            task.status = TaskStatus::Todo;
+            task.stop_session(Local::now());
+            commit(&mut store_guard, &day, Change::Paused { task }).await?;
 
             clear_active_task().await;
             drop(store_guard); 
-            persist_global_store().await?;
 
             Ok("task paused".into())
         } else {
@@ -375,39 +1314,752 @@ pub async fn command_break(parts: &[&str], _app: AppHandle) -> Result<String, St
 }
 
 // ─── /deleteT 
-// add offset stuff
-pub async fn command_deleteT(parts: &[&str], _app: AppHandle) -> Result<String, String> { 
+pub async fn command_deleteT(parts: &[&str], _app: AppHandle, days_offset: Option<i64>) -> Result<String, String> { 
     ensure_title!(parts);
     let title = parts[1..].join(" ");
 
     let mut store_guard = TASK_STORE.lock().await; 
-    let day = today_key(0);
+    let day = today_key(days_offset.unwrap_or(0));
     let bucket = bucket_mut(&mut *store_guard, &day);
 
-    let task_id_opt = bucket.todo.iter()
-        .find(|(_, t)| t.title == title)
-        .map(|(id, _)| id.clone());
-
-    let task_id = match task_id_opt {
+    // deleting the wrong task is too easy with a loose match, so a close one
+    // is only suggested
+    let task_id = match find_exact(&bucket.todo, &title)? {
         Some(id) => id,
         None => {
+            let hint = match find_task(&bucket.todo, &title) {
+                Ok(Some(id)) => {
+                    let (pos, _, t) = bucket.todo.get_full(&id).ok_or("task not found")?;
+                    format!(" did you mean '{}' ({})? give its exact title, number or id.", t.title, pos + 1)
+                }
+                Ok(None) => String::new(),
+                Err(c) => format!(" {} give the exact title, number or id.", String::from(c)),
+            };
             drop(store_guard);
-            return Err(format!("Task '{}' not found for deletion.", title));
+            return Err(format!("Task '{}' not found for deletion.{}", title, hint));
         }
     };
-    bucket.todo.remove(&task_id);
+    let title = bucket.todo.get(&task_id).map(|t| t.title.clone()).unwrap_or(title);
+    commit(&mut store_guard, &day, Change::Deleted { id: task_id.clone(), title: title.clone() }).await?;
     let current_active_id_opt = ACTIVE_TASK_ID.read().await.clone();
     if current_active_id_opt.as_deref() == Some(&task_id) {
         clear_active_task().await;
     }
+    let freed = release_blocked(&mut store_guard, &task_id).await?;
 
     drop(store_guard); 
-    persist_global_store().await?; 
 
-    Ok(format!("Task '{}' deleted.", title))
+    Ok(format!("Task '{}' deleted.{}", title, describe_freed(&freed)))
+}
+
+// ─── /rename <task> -> <new title>
+pub async fn command_rename(parts: &[&str], _app: AppHandle, days_offset: Option<i64>) -> Result<String, String> {
+    let usage = "usage: /rename <task> -> <new title>";
+    let rest = parts.get(1..).map(|p| p.join(" ")).unwrap_or_default();
+    let (key, new_title) = rest.split_once("->")
+        .map(|(k, n)| (k.trim().to_string(), n.trim().to_string()))
+        .filter(|(k, n)| !k.is_empty() && !n.is_empty())
+        .ok_or(usage)?;
+    // labels in the new title are read like /todo's: tags are added, the
+    // rest replace what the task had
+    let words: Vec<&str> = new_title.split_whitespace().collect();
+    let (words, labels) = labels::extract_labels(&words);
+    let new_title = words.join(" ");
+
+    let mut store_guard = TASK_STORE.lock().await;
+    let day = today_key(days_offset.unwrap_or(0));
+    let bucket = bucket_mut(&mut store_guard, &day);
+
+    let id = find_in_bucket(bucket, &key)?.ok_or_else(|| format!("Task '{}' not found.", key))?;
+    if bucket.todo.values().chain(bucket.done.values()).any(|t| t.title == new_title && t.id != id) {
+        return Err("duplicate title".into());
+    }
+    let Some(mut task) = bucket.todo.get(&id).or(bucket.done.get(&id)).cloned() else {
+        return Err("task found by ID but could not be renamed".into());
+    };
+    let old_title = std::mem::replace(&mut task.title, new_title.clone());
+    for t in labels.tags {
+        if !task.tags.contains(&t) {
+            task.tags.push(t);
+        }
+    }
+    task.project = labels.project.or(task.project);
+    task.priority = labels.priority.or(task.priority);
+    task.estimate = labels.estimate.or(task.estimate);
+    let renamed = task.clone();
+    commit(&mut store_guard, &day, Change::Renamed { task, old_title: old_title.clone() }).await?;
+    drop(store_guard);
+
+    // keep the running copy in step
+    if let Some(active) = ACTIVE_TASK.lock().await.as_mut().filter(|t| t.id == id) {
+        active.title = renamed.title;
+        active.tags = renamed.tags;
+        active.project = renamed.project;
+        active.priority = renamed.priority;
+        active.estimate = renamed.estimate;
+    }
+
+    Ok(format!("renamed '{}' to '{}'", old_title, new_title))
+}
+
+// ─── /undone
+pub async fn command_undone(parts: &[&str], h: AppHandle, days_offset: Option<i64>) -> Result<String, String> {
+    ensure_title!(parts);
+    let key = parts[1..].join(" ");
+
+    let mut store_guard = TASK_STORE.lock().await;
+    let day = today_key(days_offset.unwrap_or(0));
+    let bucket = bucket_mut(&mut store_guard, &day);
+
+    let id = find_task(&bucket.done, &key)?
+        .ok_or_else(|| format!("Task '{}' not found in the done list.", key))?;
+    let Some(mut task) = bucket.done.get(&id).cloned() else {
+        return Err("task found by ID but could not be reopened".into());
+    };
+    // reopening adds to the to-do list like /todo does, so the same limit holds
+    let limit = config().daily_limit;
+    if bucket.todo.len() >= limit {
+        return Err(format!("No more than {} tasks per day.", limit));
+    }
+    task.status = TaskStatus::Todo;
+    let title = task.title.clone();
+    commit(&mut store_guard, &day, Change::Reopened { task }).await?;
+    drop(store_guard);
+
+    decrement_tasks_done(h)?;
+    Ok(format!("'{}' is back on the to-do list", title))
+}
+
+// ─── /move <task> [to] <date>
+// same rules as adding the task to that day with /todo
+// the viewed day the task is on, the day it's going to, and the task typed.
+// like /todo, "tomorrow" and "+3d" count from the day on screen
+fn move_days(parts: &[&str], days_offset: Option<i64>) -> Result<(LogicalDay, LogicalDay, String), String> {
+    let usage = "usage: /move <task> [to] <date>";
+    let from = today_key(days_offset.unwrap_or(0));
+    let from_date = NaiveDate::parse_from_str(&from, "%Y-%m-%d").map_err(|e| e.to_string())?;
+    let (date, words) = dateparse::split_trailing_date(parts.get(1..).unwrap_or(&[]), from_date).ok_or(usage)?;
+    Ok((from, date.format("%Y-%m-%d").to_string(), words.join(" ")))
+}
+
+pub async fn command_move(parts: &[&str], _app: AppHandle, days_offset: Option<i64>) -> Result<String, String> {
+    let (from, to, key) = move_days(parts, days_offset)?;
+    if from == to {
+        return Err(format!("it's already on {}", to));
+    }
+
+    let mut store_guard = TASK_STORE.lock().await;
+    let source = bucket_mut(&mut store_guard, &from);
+    let id = find_task(&source.todo, &key)?
+        .ok_or_else(|| format!("Task '{}' not found in the to-do list.", key))?;
+    let task = source.todo[&id].clone();
+    if task.status == TaskStatus::Doing {
+        return Err(format!("'{}' is running. /break it first", task.title));
+    }
+
+    let target = bucket_mut(&mut store_guard, &to);
+    let limit = config().daily_limit;
+    if target.todo.len() >= limit {
+        return Err(format!("No more than {} tasks per day.", limit));
+    }
+    if target.todo.values().chain(target.done.values()).any(|t| t.title == task.title) {
+        return Err("duplicate title".into());
+    }
+    let title = task.title.clone();
+    commit(&mut store_guard, &to, Change::Moved { task, from }).await?;
+
+    Ok(format!("moved '{}' to {}", title, to))
+}
+
+// ─── checklists
+// keeps the running copy in step with a change to its task
+async fn sync_active(task: &Task) {
+    if let Some(active) = ACTIVE_TASK.lock().await.as_mut().filter(|t| t.id == task.id) {
+        *active = task.clone();
+    }
+}
+
+// the task a /sub line names and the words after it: everything before an
+// explicit "->", else the longest run of words that is a to-do task's title,
+// else just the first word as a number, id or close-enough title
+fn split_task_key<'a>(list: &IndexMap<TaskId, Task>, words: &[&'a str]) -> Result<Option<(TaskId, Vec<&'a str>)>, String> {
+    if let Some(i) = words.iter().position(|w| *w == "->") {
+        let key = words[..i].join(" ");
+        return Ok(find_task(list, &key)?.map(|id| (id, words[i + 1..].to_vec())));
+    }
+    for n in (1..words.len()).rev() {
+        let key = words[..n].join(" ").to_lowercase();
+        if let Some((id, _)) = list.iter().find(|(_, t)| t.title.to_lowercase() == key) {
+            return Ok(Some((id.clone(), words[n..].to_vec())));
+        }
+    }
+    Ok(find_task(list, words[0])?.map(|id| (id, words[1..].to_vec())))
+}
+
+// ─── /sub <task> <item>
+pub async fn command_sub(parts: &[&str], _app: AppHandle, days_offset: Option<i64>) -> Result<String, String> {
+    let usage = "usage: /sub <task> <item>";
+    let words = parts.get(1..).unwrap_or(&[]);
+    if words.len() < 2 {
+        return Err(usage.into());
+    }
+
+    let mut store_guard = TASK_STORE.lock().await;
+    let day = today_key(days_offset.unwrap_or(0));
+    let bucket = bucket_mut(&mut store_guard, &day);
+
+    let (id, item) = split_task_key(&bucket.todo, words)?
+        .ok_or_else(|| format!("no task in the to-do list matches '{}'", words.join(" ")))?;
+    if item.is_empty() {
+        return Err(usage.into());
+    }
+    let text = item.join(" ");
+    let Some(mut task) = bucket.todo.get(&id).cloned() else {
+        return Err("task found by ID but could not be changed".into());
+    };
+    if task.checklist.iter().any(|i| i.text == text) {
+        return Err(format!("'{}' already has '{}'", task.title, text));
+    }
+    task.checklist.push(ChecklistItem { text: text.clone(), done: false });
+    commit(&mut store_guard, &day, Change::Checklist { task: task.clone() }).await?;
+    drop(store_guard);
+
+    sync_active(&task).await;
+    Ok(format!("step {} of '{}': {}", task.checklist.len(), task.title, text))
+}
+
+// ─── /check <task> <n>
+// ticks step n, or unticks it if it was already ticked. with /config
+// autocomplete on, ticking the last open step finishes the task
+pub async fn command_check(parts: &[&str], h: AppHandle, days_offset: Option<i64>) -> Result<String, String> {
+    let usage = "usage: /check <task> <n>";
+    let n = parts.last().and_then(|n| n.parse::<usize>().ok()).filter(|_| parts.len() > 2).ok_or(usage)?;
+    let key = parts[1..parts.len() - 1].join(" ");
+
+    let mut store_guard = TASK_STORE.lock().await;
+    let day = today_key(days_offset.unwrap_or(0));
+    let bucket = bucket_mut(&mut store_guard, &day);
+
+    let id = find_task(&bucket.todo, &key)?
+        .ok_or_else(|| format!("Task '{}' not found in the to-do list.", key))?;
+    let Some(mut task) = bucket.todo.get(&id).cloned() else {
+        return Err("task found by ID but could not be changed".into());
+    };
+    let (title, total) = (task.title.clone(), task.checklist.len());
+    let Some(item) = n.checked_sub(1).and_then(|i| task.checklist.get_mut(i)) else {
+        return Err(match total {
+            0 => format!("'{}' has no checklist. add steps with /sub", title),
+            _ => format!("'{}' only has steps 1 to {}", title, total),
+        });
+    };
+    item.done = !item.done;
+    let (checked, text) = (item.done, item.text.clone());
+    commit(&mut store_guard, &day, Change::Checklist { task: task.clone() }).await?;
+
+    let progress = task.progress().unwrap_or(Progress { done: 0, total });
+    let msg = format!("{} '{}' ({}/{})", if checked { "checked" } else { "unchecked" }, text, progress.done, progress.total);
+    if checked && progress.done == progress.total && config().auto_complete {
+        let freed = complete_task(&mut store_guard, &day, &id, h).await?;
+        return Ok(format!("{}. '{}' is done{}", msg, title, describe_freed(&freed)));
+    }
+    drop(store_guard);
+
+    sync_active(&task).await;
+    Ok(msg)
+}
+
+// ─── dependencies
+// where a task is now, on whichever day; rollover can carry a blocker forward
+fn locate<'a>(st: &'a Store, id: &str) -> Option<(&'a LogicalDay, &'a Task)> {
+    st.iter().find_map(|(day, b)| b.todo.get(id).or_else(|| b.done.get(id)).map(|t| (day, t)))
+}
+
+// "a" or "a, b and c"
+fn and_list(items: &[String]) -> String {
+    match items.split_last() {
+        Some((last, [])) => last.clone(),
+        Some((last, rest)) => format!("{} and {}", rest.join(", "), last),
+        None => String::new(),
+    }
+}
+
+// "'a'" or "'a' and 'b'"
+fn quote_titles(titles: &[String]) -> String {
+    and_list(&titles.iter().map(|t| format!("'{}'", t)).collect::<Vec<_>>())
+}
+
+// a blocker that's since been removed some other way still shows, so it can be dropped
+fn blocker_titles(st: &Store, ids: &[TaskId]) -> String {
+    let names: Vec<String> = ids.iter()
+        .map(|id| locate(st, id).map(|(_, t)| format!("'{}'", t.title)).unwrap_or_else(|| "(missing task)".into()))
+        .collect();
+    and_list(&names)
+}
+
+// what `t` is still waiting on: blockers that aren't done, or can't be found
+fn open_blockers(st: &Store, t: &Task) -> Vec<TaskId> {
+    t.blocked_by.iter()
+        .filter(|b| locate(st, b).is_none_or(|(_, bt)| bt.status != TaskStatus::Done))
+        .cloned()
+        .collect()
+}
+
+// unfinished tasks waiting on `id` that have nothing else left to wait for,
+// now that it's done
+fn freed_by(st: &Store, id: &TaskId) -> Vec<String> {
+    st.values()
+        .flat_map(|b| b.todo.values())
+        .filter(|t| t.blocked_by.contains(id) && open_blockers(st, t).is_empty())
+        .map(|t| t.title.clone())
+        .collect()
+}
+
+fn describe_freed(freed: &[String]) -> String {
+    if freed.is_empty() { String::new() } else { format!(". {} can start now", quote_titles(freed)) }
+}
+
+// drops `id` from every task waiting on it, once it's gone. returns the
+// titles of unfinished tasks that no longer wait on anything
+async fn release_blocked(st: &mut Store, id: &TaskId) -> Result<Vec<String>, String> {
+    let waiting: Vec<(LogicalDay, Task)> = st.iter()
+        .flat_map(|(day, b)| b.todo.values().chain(b.done.values()).map(move |t| (day, t)))
+        .filter(|(_, t)| t.blocked_by.contains(id))
+        .map(|(day, t)| (day.clone(), t.clone()))
+        .collect();
+    let mut freed = Vec::new();
+    for (day, mut t) in waiting {
+        t.blocked_by.retain(|b| b != id);
+        if open_blockers(st, &t).is_empty() && t.status != TaskStatus::Done {
+            freed.push(t.title.clone());
+        }
+        commit(st, &day, Change::Blockers { task: t }).await?;
+    }
+    Ok(freed)
+}
+
+// whether `from` waits on `target`, directly or through other tasks
+fn waits_on(st: &Store, from: &str, target: &str) -> bool {
+    let mut seen: Vec<&str> = Vec::new();
+    let mut stack = vec![from];
+    while let Some(id) = stack.pop() {
+        if id == target {
+            return true;
+        }
+        if seen.contains(&id) {
+            continue;
+        }
+        seen.push(id);
+        if let Some((_, t)) = locate(st, id) {
+            stack.extend(t.blocked_by.iter().map(|b| b.as_str()));
+        }
+    }
+    false
+}
+
+// ─── /block <task> on <other>
+// titles can have "on" in them, so each "on" is tried until both sides name a task
+pub async fn command_block(parts: &[&str], _app: AppHandle, days_offset: Option<i64>) -> Result<String, String> {
+    let usage = "usage: /block <task> on <other task>";
+    let words = parts.get(1..).unwrap_or(&[]);
+
+    let mut store_guard = TASK_STORE.lock().await;
+    let day = today_key(days_offset.unwrap_or(0));
+    let bucket = bucket_mut(&mut store_guard, &day);
+
+    // a split where both sides name a task exactly beats any looser reading
+    let splits: Vec<usize> = (1..words.len().saturating_sub(1)).rev().filter(|i| words[*i] == "on").collect();
+    let exact = splits.iter().find_map(|&i| {
+        let id = find_exact(&bucket.todo, &words[..i].join(" ")).ok()??;
+        // numbers stay to-do positions; a done blocker is named by its title
+        let key = words[i + 1..].join(" ");
+        let other = find_exact(&bucket.todo, &key).ok()?
+            .or_else(|| bucket.done.iter().find(|(_, t)| t.title == key).map(|(id, _)| id.clone()))?;
+        Some((id, other))
+    });
+    let pair = exact.or_else(|| splits.iter().find_map(|&i| {
+        let id = find_task(&bucket.todo, &words[..i].join(" ")).ok()??;
+        let other = find_in_bucket(bucket, &words[i + 1..].join(" ")).ok()??;
+        Some((id, other))
+    }));
+    let (id, other) = pair.ok_or(usage)?;
+
+    let task = &bucket.todo[&id];
+    let blocker = bucket.todo.get(&other).or_else(|| bucket.done.get(&other)).ok_or(usage)?;
+    if id == other {
+        return Err("a task can't wait on itself".into());
+    }
+    if blocker.status == TaskStatus::Done {
+        return Err(format!("'{}' is already done", blocker.title));
+    }
+    if task.status == TaskStatus::Doing {
+        return Err(format!("'{}' is running. /break it first", task.title));
+    }
+    if task.blocked_by.contains(&other) {
+        return Err(format!("'{}' already waits on '{}'", task.title, blocker.title));
+    }
+    let (title, blocker_title) = (task.title.clone(), blocker.title.clone());
+    if waits_on(&store_guard, &other, &id) {
+        return Err(format!("'{}' already waits on '{}', so that would go in a circle", blocker_title, title));
+    }
+
+    let Some(mut task) = store_guard.get(&day).and_then(|b| b.todo.get(&id)).cloned() else {
+        return Err("task found by ID but could not be changed".into());
+    };
+    task.blocked_by.push(other);
+    commit(&mut store_guard, &day, Change::Blockers { task }).await?;
+    Ok(format!("'{}' now waits on '{}'", title, blocker_title))
+}
+
+// ─── /unblock <task> [from <other>]
+pub async fn command_unblock(parts: &[&str], _app: AppHandle, days_offset: Option<i64>) -> Result<String, String> {
+    ensure_title!(parts);
+    let words = &parts[1..];
+    let (key, from) = match words.iter().rposition(|w| *w == "from") {
+        Some(i) if i > 0 && i + 1 < words.len() => (words[..i].join(" "), Some(words[i + 1..].join(" "))),
+        _ => (words.join(" "), None),
+    };
+
+    let mut store_guard = TASK_STORE.lock().await;
+    let day = today_key(days_offset.unwrap_or(0));
+    let bucket = bucket_mut(&mut store_guard, &day);
+    let id = find_task(&bucket.todo, &key)?
+        .ok_or_else(|| format!("Task '{}' not found in the to-do list.", key))?;
+    let task = bucket.todo[&id].clone();
+    if task.blocked_by.is_empty() {
+        return Err(format!("'{}' isn't waiting on anything", task.title));
+    }
+
+    let missing: Vec<TaskId> = task.blocked_by.iter().filter(|b| locate(&store_guard, b).is_none()).cloned().collect();
+    let dropped: Vec<TaskId> = match from {
+        Some(from) if from == "missing" && !missing.is_empty() => missing,
+        Some(from) => {
+            // the blocker is looked up among what this task waits on, wherever it is now
+            let waiting: IndexMap<TaskId, Task> = task.blocked_by.iter()
+                .filter_map(|b| locate(&store_guard, b).map(|(_, t)| (b.clone(), t.clone())))
+                .collect();
+            let other = find_task(&waiting, &from)?
+                .ok_or_else(|| format!("'{}' doesn't wait on '{}'", task.title, from))?;
+            vec![other]
+        }
+        None => task.blocked_by.clone(),
+    };
+    let names = blocker_titles(&store_guard, &dropped);
+
+    let mut task = task;
+    task.blocked_by.retain(|b| !dropped.contains(b));
+    commit(&mut store_guard, &day, Change::Blockers { task: task.clone() }).await?;
+    if task.blocked_by.is_empty() {
+        Ok(format!("'{}' no longer waits on {}", task.title, names))
+    } else {
+        Ok(format!("'{}' no longer waits on {}, but still on {}", task.title, names, blocker_titles(&store_guard, &task.blocked_by)))
+    }
+}
+
+// ─── blocked chains
+// a blocked task and, under it, whatever it's waiting on
+#[derive(Debug, Clone, Serialize)]
+pub struct BlockNode {
+    pub id: TaskId,
+    pub title: String,
+    pub day: LogicalDay,
+    pub status: TaskStatus,
+    pub waiting_on: Vec<BlockNode>,
+}
+
+fn block_node(st: &Store, day: &LogicalDay, t: &Task, path: &mut Vec<TaskId>) -> BlockNode {
+    path.push(t.id.clone());
+    let mut waiting_on = Vec::new();
+    for b in open_blockers(st, t) {
+        // a cycle can't be made with /block, but don't hang if one turns up
+        if path.contains(&b) {
+            continue;
+        }
+        match locate(st, &b) {
+            Some((d, bt)) => waiting_on.push(block_node(st, d, bt, path)),
+            None => waiting_on.push(BlockNode { id: b, title: "(missing task)".into(), day: day.clone(), status: TaskStatus::Todo, waiting_on: Vec::new() }),
+        }
+    }
+    path.pop();
+    BlockNode { id: t.id.clone(), title: t.title.clone(), day: day.clone(), status: t.status, waiting_on }
+}
+
+// the day's blocked tasks that nothing else on the day waits on, each with
+// the chain of tasks holding it up
+fn blocked_chains(st: &Store, day: &LogicalDay) -> Vec<BlockNode> {
+    let Some(bucket) = st.get(day) else { return Vec::new() };
+    let blocked: Vec<(&Task, Vec<TaskId>)> = bucket.todo.values()
+        .map(|t| (t, open_blockers(st, t)))
+        .filter(|(_, open)| !open.is_empty())
+        .collect();
+    blocked.iter()
+        .filter(|(t, _)| !blocked.iter().any(|(_, open)| open.contains(&t.id)))
+        .map(|(t, _)| t)
+        .map(|t| block_node(st, day, t, &mut Vec::new()))
+        .collect()
 }
 
-// ─── /completed (placeholder)
-pub fn command_completed() -> Result<String, String> {
-    Ok("success".into())
+#[tauri::command]
+pub async fn get_blocked(day: String) -> Vec<BlockNode> {
+    blocked_chains(&*TASK_STORE.lock().await, &day)
+}
+
+fn render_chain(node: &BlockNode, viewed: &LogicalDay, depth: usize, out: &mut Vec<String>) {
+    let prefix = if depth == 0 { String::new() } else { format!("{}└ ", "  ".repeat(depth - 1)) };
+    let elsewhere = if &node.day != viewed { format!(" ({})", node.day) } else { String::new() };
+    out.push(format!("{}{}{}", prefix, node.title, elsewhere));
+    for child in &node.waiting_on {
+        render_chain(child, viewed, depth + 1, out);
+    }
+}
+
+// ─── /blocked
+pub async fn command_blocked(_parts: &[&str], _app: AppHandle, days_offset: Option<i64>) -> Result<String, String> {
+    let day = today_key(days_offset.unwrap_or(0));
+    let chains = blocked_chains(&*TASK_STORE.lock().await, &day);
+    if chains.is_empty() {
+        return Ok(format!("nothing is blocked on {}", day));
+    }
+    let mut out = Vec::new();
+    for chain in &chains {
+        render_chain(chain, &day, 0, &mut out);
+    }
+    Ok(out.join("\n"))
+}
+
+// ─── /history
+// when a task was created, started, paused and finished, from the journal
+pub async fn command_history(parts: &[&str], _app: AppHandle, days_offset: Option<i64>) -> Result<String, String> {
+    ensure_title!(parts);
+    let key = parts[1..].join(" ");
+    let day = today_key(days_offset.unwrap_or(0));
+    let bucket = TASK_STORE.lock().await.get(&day).cloned().unwrap_or_default();
+
+    let id = find_in_bucket(&bucket, &key)?.ok_or("task not found")?;
+    let title = bucket.todo.get(&id).or_else(|| bucket.done.get(&id)).map(|t| t.title.clone()).unwrap_or(key);
+
+    let lines: Vec<String> = journal::task_history(&id)?
+        .iter()
+        .filter(|ev| !matches!(ev.change, Change::Ticked { .. }))
+        .map(|ev| format!("{} {}", ev.at.format("%Y-%m-%d %H:%M"), ev.change.name()))
+        .collect();
+    if lines.is_empty() {
+        return Ok(format!("no history for '{}' yet", title));
+    }
+    Ok(format!("'{}'\n{}", title, lines.join("\n")))
+}
+
+// ─── completed history
+#[derive(Debug, Clone, Serialize)]
+pub struct CompletedDay {
+    pub day: LogicalDay,
+    pub tasks: Vec<Task>,
+    pub time_spent: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct CompletedReport {
+    // None when the query covers every day in the store
+    pub from: Option<LogicalDay>,
+    pub to: Option<LogicalDay>,
+    pub days: Vec<CompletedDay>,
+    pub total_tasks: usize,
+    pub total_time: u64,
+}
+
+// empty range means all time
+async fn completed_report(range: &[&str]) -> Result<CompletedReport, String> {
+    let bounds = if range.is_empty() {
+        None
+    } else {
+        let today = NaiveDate::parse_from_str(&today_key(0), "%Y-%m-%d").map_err(|e| e.to_string())?;
+        let (a, b) = dateparse::parse_range(range, today).ok_or_else(|| format!(
+            "couldn't understand '{}'. try last week, this month, last 7 days or 2025-06-01..2025-06-30",
+            range.join(" ")
+        ))?;
+        Some((a.format("%Y-%m-%d").to_string(), b.format("%Y-%m-%d").to_string()))
+    };
+
+    let store_guard = TASK_STORE.lock().await;
+    let mut days: Vec<CompletedDay> = store_guard.iter()
+        .filter(|(day, b)| !b.done.is_empty() && bounds.as_ref().is_none_or(|(a, z)| *day >= a && *day <= z))
+        .map(|(day, b)| CompletedDay {
+            day: day.clone(),
+            tasks: b.done.values().cloned().collect(),
+            time_spent: b.done.values().map(|t| t.time_spent).sum(),
+        })
+        .collect();
+    drop(store_guard);
+    days.sort_by(|a, b| a.day.cmp(&b.day));
+
+    let (from, to) = bounds.unzip();
+    Ok(CompletedReport {
+        from,
+        to,
+        total_tasks: days.iter().map(|d| d.tasks.len()).sum(),
+        total_time: days.iter().map(|d| d.time_spent).sum(),
+        days,
+    })
+}
+
+#[tauri::command]
+pub async fn get_completed(range: Option<String>) -> Result<CompletedReport, String> {
+    let range = range.unwrap_or_default();
+    completed_report(&range.split_whitespace().collect::<Vec<_>>()).await
+}
+
+// ─── /completed [range]
+pub async fn command_completed(parts: &[&str], _app: AppHandle) -> Result<String, String> {
+    let report = completed_report(parts.get(1..).unwrap_or(&[])).await?;
+    if report.days.is_empty() {
+        return Ok("nothing completed in that range".into());
+    }
+    let mut out = Vec::new();
+    for d in &report.days {
+        out.push(format!("{} ({} done, {})", d.day, d.tasks.len(), format_duration(d.time_spent)));
+        for t in &d.tasks {
+            out.push(format!("  {} {}", t.title, format_duration(t.time_spent)));
+        }
+    }
+    out.push(format!("total: {} tasks, {}", report.total_tasks, format_duration(report.total_time)));
+    Ok(out.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(titles: &[(&str, &str)]) -> IndexMap<TaskId, Task> {
+        titles.iter()
+            .map(|(id, title)| {
+                let task = Task { id: id.to_string(), ..Task::new(title.to_string(), "2025-06-04".into()) };
+                (task.id.clone(), task)
+            })
+            .collect()
+    }
+
+    fn tasks() -> IndexMap<TaskId, Task> {
+        list(&[("1111aaaa", "write report"), ("2222bbbb", "call bob"), ("3333cccc", "fix login bug"), ("4444dddd", "fix logout bug")])
+    }
+
+    fn found(key: &str) -> Option<String> {
+        find_task(&tasks(), key).expect("one task").map(|id| tasks()[&id].title.clone())
+    }
+
+    #[test]
+    fn exact_title_position_and_id_prefix() {
+        assert_eq!(found("call bob").as_deref(), Some("call bob"));
+        assert_eq!(found("2").as_deref(), Some("call bob"));
+        assert_eq!(found("3333").as_deref(), Some("fix login bug"));
+    }
+
+    #[test]
+    fn loose_matches() {
+        assert_eq!(found("CALL BOB").as_deref(), Some("call bob"));
+        assert_eq!(found("report").as_deref(), Some("write report"));
+        assert_eq!(found("cal bob").as_deref(), Some("call bob"));
+        assert_eq!(found("zzzz qqqq"), None);
+    }
+
+    #[test]
+    fn several_substring_hits_are_candidates() {
+        let c = find_task(&tasks(), "fix").unwrap_err();
+        assert!(!c.close_only);
+        let mut positions: Vec<usize> = c.tasks.iter().map(|t| t.position).collect();
+        positions.sort();
+        assert_eq!(positions, vec![3, 4]);
+    }
+
+    #[test]
+    fn fuzzy_needs_a_clear_winner() {
+        // "fix logn bug" is much nearer login than logout
+        assert_eq!(found("fix logn bug").as_deref(), Some("fix login bug"));
+        // "fix lo bug" is about as near either, so both are suggested
+        let c = find_task(&tasks(), "fix lo bug").unwrap_err();
+        assert!(c.close_only);
+        assert_eq!(c.tasks.len(), 2);
+        assert!(String::from(c).starts_with("no task 'fix lo bug'. did you mean"));
+    }
+
+    // a waits on b, b waits on c; c is on another day and finished
+    fn chain() -> Store {
+        let mut today = list(&[("a", "a"), ("b", "b")]);
+        today["a"].blocked_by = vec!["b".into()];
+        today["b"].blocked_by = vec!["c".into()];
+        let mut earlier = list(&[("c", "c")]);
+        earlier["c"].status = TaskStatus::Done;
+        let mut st = Store::new();
+        st.insert("2025-06-03".into(), DayBucket { todo: IndexMap::new(), done: earlier });
+        st.insert("2025-06-04".into(), DayBucket { todo: today, done: IndexMap::new() });
+        st
+    }
+
+    #[test]
+    fn waits_on_follows_the_chain() {
+        let st = chain();
+        assert!(waits_on(&st, "a", "b"));
+        assert!(waits_on(&st, "a", "c"));
+        assert!(!waits_on(&st, "c", "a"));
+        assert!(!waits_on(&st, "b", "a"));
+    }
+
+    #[test]
+    fn waits_on_stops_on_a_cycle() {
+        let mut st = chain();
+        st["2025-06-03"].done["c"].blocked_by = vec!["a".into()];
+        assert!(waits_on(&st, "c", "b"));
+        assert!(!waits_on(&st, "a", "zzz"));
+    }
+
+    #[test]
+    fn done_blockers_are_cleared_until_reopened() {
+        let mut st = chain();
+        assert!(open_blockers(&st, &st["2025-06-04"].todo["b"]).is_empty());
+        assert_eq!(open_blockers(&st, &st["2025-06-04"].todo["a"]), vec!["b"]);
+        assert_eq!(freed_by(&st, &"c".to_string()), vec!["b"]);
+
+        st["2025-06-03"].done["c"].status = TaskStatus::Todo;
+        assert_eq!(open_blockers(&st, &st["2025-06-04"].todo["b"]), vec!["c"]);
+        assert!(freed_by(&st, &"c".to_string()).is_empty());
+    }
+
+    #[test]
+    fn missing_blockers_stay_open_and_are_named() {
+        let mut st = chain();
+        st["2025-06-04"].todo["a"].blocked_by.push("gone".into());
+        let a = st["2025-06-04"].todo["a"].clone();
+        assert_eq!(open_blockers(&st, &a), vec!["b", "gone"]);
+        assert_eq!(blocker_titles(&st, &a.blocked_by), "'b' and (missing task)");
+    }
+
+    #[test]
+    fn move_dates_count_from_the_viewed_day() {
+        let words: Vec<&str> = "/move write report tomorrow".split_whitespace().collect();
+        // three days back, "tomorrow" is two days back
+        assert_eq!(move_days(&words, Some(3)).unwrap(), (today_key(3), today_key(2), "write report".to_string()));
+        assert_eq!(move_days(&words, None).unwrap(), (today_key(0), today_key(-1), "write report".to_string()));
+        let words: Vec<&str> = "/move 2 +3d".split_whitespace().collect();
+        assert_eq!(move_days(&words, Some(5)).unwrap().1, today_key(2));
+    }
+
+    #[test]
+    fn numbers_past_the_todo_list_are_not_done_tasks() {
+        let mut bucket = DayBucket { todo: list(&[("1111aaaa", "write report"), ("2222bbbb", "call bob")]), done: list(&[("3333cccc", "standup")]) };
+        bucket.done["3333cccc"].status = TaskStatus::Done;
+        assert_eq!(find_in_bucket(&bucket, "2").unwrap().as_deref(), Some("2222bbbb"));
+        let err = find_in_bucket(&bucket, "3").unwrap_err();
+        assert!(err.contains("no task 3 on the to-do list"), "{}", err);
+        assert!(find_in_bucket(&bucket, "0").is_err());
+        assert_eq!(find_in_bucket(&bucket, "d1").unwrap().as_deref(), Some("3333cccc"));
+        assert!(find_in_bucket(&bucket, "d2").is_err());
+        assert_eq!(find_in_bucket(&bucket, "standup").unwrap().as_deref(), Some("3333cccc"));
+    }
+
+    #[test]
+    fn exact_lookup_ignores_loose_matches() {
+        assert_eq!(find_exact(&tasks(), "report").unwrap(), None);
+        assert_eq!(find_exact(&tasks(), "CALL BOB").unwrap(), None);
+        assert_eq!(find_exact(&tasks(), "1").unwrap().as_deref(), Some("1111aaaa"));
+        let shared = list(&[("abcd1111", "one"), ("abcd2222", "two")]);
+        assert_eq!(find_exact(&shared, "abcd").unwrap_err().tasks.len(), 2);
+    }
 }
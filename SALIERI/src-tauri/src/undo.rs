@@ -0,0 +1,278 @@
+use std::cell::RefCell;
+use std::future::Future;
+use std::sync::Mutex;
+use indexmap::IndexMap;
+use lazy_static::lazy_static;
+use serde::{Serialize, Deserialize};
+use tauri::AppHandle;
+
+use crate::recurrence;
+use crate::storage::{self, data_dir, load_json_or_quarantine, save_json};
+use crate::tasks::{self, Placed, PutBack, TaskId};
+use crate::theme::{get_current_theme, set_theme};
+use crate::user::User;
+
+// how many commands /undo can walk back
+const UNDO_LIMIT: usize = 50;
+
+// one task a command changed; None means it wasn't in the store
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TaskChange {
+    id: TaskId,
+    before: Option<Placed>,
+    after: Option<Placed>,
+}
+
+// what isn't a task: one side (before or after) of a recorded command
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+struct Side {
+    active: Option<TaskId>,
+    theme: String,
+    tasks_done: u64,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct UndoEntry {
+    command: String,
+    tasks: Vec<TaskChange>,
+    before: Side,
+    after: Side,
+}
+
+impl UndoEntry {
+    fn touches(&self, ids: &[TaskId]) -> bool {
+        self.tasks.iter().any(|c| ids.contains(&c.id))
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct History {
+    undo: Vec<UndoEntry>,
+    redo: Vec<UndoEntry>,
+    // why undo.json couldn't be read. it's kept as it is until then, so
+    // history only lives in memory
+    #[serde(skip)]
+    broken: Option<String>,
+    #[serde(skip)]
+    saves: u64,
+}
+
+lazy_static! {
+    static ref HISTORY: Mutex<History> = Mutex::new(load_history());
+    // the last numbered save that reached the disk
+    static ref WRITTEN: Mutex<u64> = Mutex::new(0);
+}
+
+pub fn history_path() -> std::path::PathBuf { data_dir().join("undo.json") }
+
+fn load_history() -> History {
+    match load_json_or_quarantine(&history_path()) {
+        Ok(h) => h,
+        Err(e) if e == "missing" => History::default(),
+        Err(e) => {
+            eprintln!("undo.json failed to load: {}", e);
+            History { broken: Some(e), ..History::default() }
+        }
+    }
+}
+
+// written off the async runtime, since this can run under the task store
+// lock. each save is numbered so a slower, older one can't land last
+fn save_history(h: &mut History) {
+    if h.broken.is_some() {
+        return;
+    }
+    h.saves += 1;
+    let n = h.saves;
+    let json = match serde_json::to_value(&*h) {
+        Ok(json) => json,
+        Err(e) => {
+            eprintln!("failed to save undo.json: {}", e);
+            return;
+        }
+    };
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut written = WRITTEN.lock().unwrap();
+        if n <= *written {
+            return;
+        }
+        match save_json(&history_path(), &json) {
+            Ok(()) => *written = n,
+            Err(e) => eprintln!("failed to save undo.json: {}", e),
+        }
+    });
+}
+
+// after /restore the recorded tasks no longer line up with the store. the
+// history starts over, replacing a broken undo.json (its copy is kept aside)
+pub fn clear_history() {
+    let mut h = HISTORY.lock().unwrap();
+    *h = History { saves: h.saves, ..History::default() };
+    save_history(&mut h);
+}
+
+// ─── recording
+// how the task changes made by the current async task are treated
+enum Tracking {
+    // a recorded command: keep where each task started and where it ended up
+    Record(IndexMap<TaskId, TaskChange>),
+    // /undo or /redo putting tasks back; the history already knows
+    Replay,
+}
+
+tokio::task_local! {
+    static TRACKING: RefCell<Tracking>;
+}
+
+// commands handle_palette_command records. /every and /todo every are left
+// out: the rule lives on in /recurring, so undoing just the task would be misleading
+pub fn is_undoable(parts: &[&str]) -> bool {
+    !recurrence::is_rule_command(parts) && matches!(parts.first(), Some(&"/todo" | &"/doing" | &"/done" | &"/break" | &"/deleteT" | &"/theme"
+        | &"/rename" | &"/undone" | &"/move" | &"/import" | &"/sub" | &"/check"
+        | &"/block" | &"/unblock"))
+}
+
+// called by tasks::commit, under the store lock, for every task a change
+// touches. changes from outside a recorded command (rollover, recurring
+// tasks, /recover...) make any history about that task stale, so it's dropped.
+pub(crate) fn track_change(id: &TaskId, before: Option<Placed>, after: Option<Placed>) {
+    // the timer ticking isn't something to undo
+    if tasks::same_place(before.as_ref(), after.as_ref()) {
+        return;
+    }
+    let tracked = TRACKING.try_with(|t| {
+        if let Tracking::Record(changes) = &mut *t.borrow_mut() {
+            changes.entry(id.clone())
+                .and_modify(|c| c.after = after.clone())
+                .or_insert_with(|| TaskChange { id: id.clone(), before, after: after.clone() });
+        }
+    });
+    if tracked.is_err() {
+        forget(std::slice::from_ref(id));
+    }
+}
+
+// drops every undo and redo step that involves one of `ids`
+fn forget(ids: &[TaskId]) {
+    let mut h = HISTORY.lock().unwrap();
+    let count = h.undo.len() + h.redo.len();
+    h.undo.retain(|e| !e.touches(ids));
+    h.redo.retain(|e| !e.touches(ids));
+    if h.undo.len() + h.redo.len() != count {
+        save_history(&mut h);
+    }
+}
+
+// runs a command, collecting the task changes it makes along the way
+pub async fn track<T>(command: impl Future<Output = T>) -> (T, Vec<TaskChange>) {
+    TRACKING.scope(RefCell::new(Tracking::Record(IndexMap::new())), async move {
+        let out = command.await;
+        let changes = match TRACKING.with(|t| t.replace(Tracking::Replay)) {
+            Tracking::Record(changes) => changes.into_values().collect(),
+            Tracking::Replay => Vec::new(),
+        };
+        (out, changes)
+    }).await
+}
+
+// the running task, theme and done count, taken before a command runs
+pub struct Checkpoint(Side);
+
+pub async fn checkpoint(app: &AppHandle) -> Checkpoint {
+    Checkpoint(Side {
+        active: tasks::active_task_id().await,
+        theme: get_current_theme(app.clone()).await.unwrap_or_default(),
+        tasks_done: User::load_user(app).map(|u| u.tasks_done).unwrap_or(0),
+    })
+}
+
+// pushes what the command changed. a command that failed part way still
+// changed whatever it got to, and there's no step to undo that, so any
+// history about those tasks goes.
+pub async fn record(command: &str, before: Checkpoint, changes: Vec<TaskChange>, ok: bool, app: &AppHandle) {
+    if !ok {
+        forget(&changes.into_iter().map(|c| c.id).collect::<Vec<_>>());
+        return;
+    }
+    let Checkpoint(before) = before;
+    let Checkpoint(after) = checkpoint(app).await;
+    let tasks: Vec<TaskChange> = changes.into_iter()
+        .filter(|c| !tasks::same_place(c.before.as_ref(), c.after.as_ref()))
+        .collect();
+    if tasks.is_empty() && before == after {
+        return;
+    }
+
+    let entry = UndoEntry { command: command.to_string(), tasks, before, after };
+    let mut h = HISTORY.lock().unwrap();
+    h.undo.push(entry);
+    let overflow = h.undo.len().saturating_sub(UNDO_LIMIT);
+    h.undo.drain(..overflow);
+    h.redo.clear();
+    save_history(&mut h);
+}
+
+// ─── /undo, /redo
+// moves the app from one side of `entry` to the other
+async fn apply(entry: &UndoEntry, undo: bool, app: &AppHandle) -> Result<(), String> {
+    let (to, from) = if undo { (&entry.before, &entry.after) } else { (&entry.after, &entry.before) };
+    let puts: Vec<PutBack> = entry.tasks.iter()
+        .map(|c| {
+            let (now, to) = if undo { (&c.after, &c.before) } else { (&c.before, &c.after) };
+            PutBack { id: &c.id, now: now.as_ref(), to: to.as_ref() }
+        })
+        .collect();
+    TRACKING.scope(RefCell::new(Tracking::Replay), tasks::put_back(&puts)).await?;
+
+    if to.active != from.active {
+        tasks::restore_active(to.active.clone()).await;
+    }
+
+    if to.theme != from.theme && !to.theme.is_empty() {
+        set_theme(app.clone(), to.theme.clone()).await?;
+    }
+
+    if to.tasks_done != from.tasks_done {
+        // shift by the difference so tasks finished since aren't lost
+        let mut user = User::load_user(app)?;
+        user.tasks_done = (user.tasks_done + to.tasks_done).saturating_sub(from.tasks_done);
+        User::save_user(app, &user)?;
+    }
+    Ok(())
+}
+
+pub async fn command_undo(_parts: &[&str], app: AppHandle) -> Result<String, String> {
+    storage::ensure_writable()?;
+    let entry = {
+        let mut h = HISTORY.lock().unwrap();
+        let broken = h.broken.as_ref().map(|e| format!(". undo.json couldn't be read, so older history is gone: {}", e));
+        h.undo.pop().ok_or_else(|| format!("nothing to undo{}", broken.unwrap_or_default()))?
+    };
+
+    if let Err(e) = apply(&entry, true, &app).await {
+        HISTORY.lock().unwrap().undo.push(entry);
+        return Err(e);
+    }
+
+    let msg = format!("undid '{}'", entry.command);
+    let mut h = HISTORY.lock().unwrap();
+    h.redo.push(entry);
+    save_history(&mut h);
+    Ok(msg)
+}
+
+pub async fn command_redo(_parts: &[&str], app: AppHandle) -> Result<String, String> {
+    storage::ensure_writable()?;
+    let entry = HISTORY.lock().unwrap().redo.pop().ok_or("nothing to redo")?;
+
+    if let Err(e) = apply(&entry, false, &app).await {
+        HISTORY.lock().unwrap().redo.push(entry);
+        return Err(e);
+    }
+
+    let msg = format!("redid '{}'", entry.command);
+    let mut h = HISTORY.lock().unwrap();
+    h.undo.push(entry);
+    save_history(&mut h);
+    Ok(msg)
+}
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use serde::{Serialize, Deserialize};
 use tauri_plugin_store::StoreExt;
 use tauri::AppHandle;
@@ -10,6 +11,9 @@ pub struct User {
     pub tasks_done: u64,
     pub pomodoro_done: u64,
     pub time_in_salieri: u64,
+    // finished pomodoros per logical day, for reports
+    #[serde(default)]
+    pub pomodoros_by_day: BTreeMap<String, u64>,
 }
 
 impl User {
@@ -36,9 +40,18 @@ pub fn increment_tasks_done(app: AppHandle) -> Result<String, String> {
     Ok(format!("tasks done: {}", user.tasks_done))
 }
 
+// for /undone
+pub fn decrement_tasks_done(app: AppHandle) -> Result<String, String> {
+    let mut user = User::load_user(&app)?;
+    user.tasks_done = user.tasks_done.saturating_sub(1);
+    User::save_user(&app, &user)?;
+    Ok(format!("tasks done: {}", user.tasks_done))
+}
+
 pub fn increment_pomodoros_done(app: AppHandle) -> Result<String, String> {
     let mut user = User::load_user(&app)?;
     user.pomodoro_done += 1;
+    *user.pomodoros_by_day.entry(crate::tasks::get_current_logical_day_key(None)).or_insert(0) += 1;
     User::save_user(&app, &user)?;
     Ok(format!("tasks done: {}", user.pomodoro_done))
 }
\ No newline at end of file
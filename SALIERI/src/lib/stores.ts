@@ -6,8 +6,34 @@ export type Task = {
   id: string;
   title: string;
   created_at: string;
-  status: string;
+  status: 'todo' | 'doing' | 'done';
   time_spent: number; 
+  carried_over: number;
+  recurrence_id?: string;
+  sessions?: WorkSession[];
+  untimed?: number;
+  tags?: string[];
+  project?: string;
+  priority?: number;
+  estimate?: number;
+  checklist?: ChecklistItem[];
+  // steps ticked so far; only on tasks with a checklist
+  progress?: { done: number; total: number };
+  // ids of the tasks this one waits on, done or not
+  blocked_by?: string[];
+  // still waits on something that isn't done
+  blocked?: boolean;
+};
+
+export type ChecklistItem = {
+  text: string;
+  done: boolean;
+};
+
+// one /doing -> /break or /done span; end is missing while it's still running
+export type WorkSession = {
+  start: string;
+  end?: string;
 };
 
 export const tasks = writable<Task[]>([]);